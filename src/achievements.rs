@@ -0,0 +1,212 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadDirectError, io::Reader};
+use bevy::prelude::*;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::locale::Localization;
+use crate::storage::Persistent;
+use crate::waves::{CAMPAIGN_PATH, Campaign};
+
+/// Path of the achievement definitions, relative to `resources/`
+pub const ACHIEVEMENTS_PATH: &str = "game.achievements.ron";
+
+/// Every achievement that can be unlocked, loaded from `resources/game.achievements.ron`.
+/// The resource starts empty and is filled once the asset has loaded.
+#[derive(Asset, Resource, TypePath, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AchievementList {
+    pub achievements: Vec<AchievementDefinition>,
+}
+
+/// One achievement: a stable `id` used in saves, the text shown to players,
+/// and the condition that unlocks it
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AchievementDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: AchievementCondition,
+}
+
+/// What has to happen to unlock an achievement
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AchievementCondition {
+    /// Destroy this many enemies in one run
+    Kills(u32),
+    /// Hit this many saucers (special spawns) in one run
+    SaucersHit(u32),
+    /// Reach this score in one run
+    Score(u32),
+    /// Reach this wave, counted from 1
+    ReachWave(usize),
+    /// Clear a wave with every player shot hitting an enemy
+    PerfectWave,
+    /// Destroy the last enemy of a wave within `seconds` of the previous kill
+    QuickFinish { seconds: f32 },
+    /// Clear a wave without moving left
+    NoRetreat,
+    /// Clear the last wave of the campaign
+    Victory,
+}
+
+/// Errors raised while loading achievement definitions
+#[derive(Debug, Error)]
+pub enum AchievementListError {
+    #[error("could not read achievements: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse achievements: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not load the campaign: {0}")]
+    Campaign(Box<LoadDirectError>),
+    #[error("duplicate achievement id '{0}'")]
+    DuplicateId(String),
+    #[error("achievement '{id}' needs wave {wave}, but the campaign has {waves} waves")]
+    UnreachableWave {
+        id: String,
+        wave: usize,
+        waves: usize,
+    },
+}
+
+impl AchievementDefinition {
+    /// Name in the current language; translations key it by the achievement's id
+    pub fn localized_name(&self, localization: &Localization) -> String {
+        localization.text_or(&format!("achievement.{}.name", self.id), &self.name)
+    }
+
+    pub fn localized_description(&self, localization: &Localization) -> String {
+        localization.text_or(
+            &format!("achievement.{}.description", self.id),
+            &self.description,
+        )
+    }
+}
+
+impl AchievementList {
+    pub fn get(&self, id: &str) -> Option<&AchievementDefinition> {
+        self.achievements
+            .iter()
+            .find(|achievement| achievement.id == id)
+    }
+
+    /// Check that every achievement can be told apart in saves and can be
+    /// unlocked in a campaign of `waves` waves
+    pub fn validate(&self, waves: usize) -> Result<(), AchievementListError> {
+        for (index, achievement) in self.achievements.iter().enumerate() {
+            if self.achievements[..index]
+                .iter()
+                .any(|other| other.id == achievement.id)
+            {
+                return Err(AchievementListError::DuplicateId(achievement.id.clone()));
+            }
+            if let AchievementCondition::ReachWave(wave) = achievement.condition
+                && wave > waves
+            {
+                return Err(AchievementListError::UnreachableWave {
+                    id: achievement.id.clone(),
+                    wave,
+                    waves,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Asset loader for `*.achievements.ron` files
+#[derive(Default, TypePath)]
+pub struct AchievementListLoader;
+
+impl AssetLoader for AchievementListLoader {
+    type Asset = AchievementList;
+    type Settings = ();
+    type Error = AchievementListError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<AchievementList, AchievementListError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let list: AchievementList = ron::de::from_bytes(&bytes)?;
+        // Loaded as a dependency, so editing the campaign checks the list again
+        let campaign = load_context
+            .loader()
+            .immediate()
+            .load::<Campaign>(CAMPAIGN_PATH)
+            .await
+            .map_err(|error| AchievementListError::Campaign(Box::new(error)))?;
+        list.validate(campaign.get().waves.len())?;
+        Ok(list)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+/// An unlocked achievement and when it was unlocked
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Unlock {
+    pub id: String,
+    pub at: NaiveDateTime,
+}
+
+/// Achievements unlocked so far, saved between sessions
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct UnlockedAchievements {
+    pub unlocks: Vec<Unlock>,
+}
+
+impl Persistent for UnlockedAchievements {
+    const KEY: &'static str = "achievements";
+    const VERSION: u32 = 1;
+}
+
+impl UnlockedAchievements {
+    pub fn get(&self, id: &str) -> Option<&Unlock> {
+        self.unlocks.iter().find(|unlock| unlock.id == id)
+    }
+
+    /// Record an unlock, returning `false` if it was already unlocked
+    pub fn unlock(&mut self, id: &str, at: NaiveDateTime) -> bool {
+        if self.get(id).is_some() {
+            return false;
+        }
+        self.unlocks.push(Unlock {
+            id: id.to_string(),
+            at,
+        });
+        true
+    }
+}
+
+/// Progress towards the per-wave achievements in the current run
+#[derive(Resource, Default)]
+pub struct AchievementProgress {
+    /// Player shots fired and hits scored in the current wave
+    pub wave_shots: u32,
+    pub wave_hits: u32,
+    /// The player moved left during the current wave
+    pub moved_left: bool,
+    /// Last known player position, to detect moving left
+    pub player_x: Option<f32>,
+    /// Times of the two most recent kills, in seconds since startup
+    pub last_kill: Option<f32>,
+    pub previous_kill: Option<f32>,
+}
+
+impl AchievementProgress {
+    /// Start tracking a new wave
+    pub fn start_wave(&mut self) {
+        *self = Self {
+            player_x: self.player_x,
+            ..default()
+        };
+    }
+}
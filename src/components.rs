@@ -1,4 +1,9 @@
+use std::ops::BitOr;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::TapTarget;
 
 /// Player ship component marker
 #[derive(Component)]
@@ -10,6 +15,26 @@ pub struct Enemy {
     pub original_x: f32,
 }
 
+/// Enemy types, each with its own sprite
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnemyKind {
+    Type1,
+    Type2,
+    Type3,
+}
+
+impl EnemyKind {
+    /// Parse a wave formation symbol (`1`, `2` or `3`)
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '1' => Some(Self::Type1),
+            '2' => Some(Self::Type2),
+            '3' => Some(Self::Type3),
+            _ => None,
+        }
+    }
+}
+
 /// Player bullet component marker
 #[derive(Component)]
 pub struct Bullet;
@@ -18,24 +43,198 @@ pub struct Bullet;
 #[derive(Component)]
 pub struct EnemyBullet;
 
-/// Velocity component for moving entities
+/// Velocity component for moving entities, in units per second
 #[derive(Component)]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
 }
 
+/// Change of velocity per second, e.g. gravity on debris or homing steering
+#[derive(Component)]
+pub struct Acceleration {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Fraction of velocity lost per second, applied exponentially
+#[derive(Component)]
+pub struct Drag(pub f32);
+
+/// Rotation around the z axis, in radians per second
+#[derive(Component)]
+pub struct AngularVelocity(pub f32);
+
 /// Score UI component marker
 #[derive(Component)]
 pub struct Score;
 
-/// Start screen UI component marker
+/// Root node of the in-game HUD
+#[derive(Component)]
+pub struct HudRoot;
+
+/// HUD high score text
+#[derive(Component)]
+pub struct HighScoreText;
+
+/// HUD wave number text
+#[derive(Component)]
+pub struct WaveText;
+
+/// HUD row of ship icons, one per life left
+#[derive(Component)]
+pub struct LivesDisplay;
+
+/// One ship icon in the `LivesDisplay`
+#[derive(Component)]
+pub struct LifeIcon;
+
+/// HUD combo count text
+#[derive(Component)]
+pub struct ComboText;
+
+/// HUD bar showing the time left to extend the combo
+#[derive(Component)]
+pub struct ComboBar;
+
+/// Filled part of the `ComboBar`
+#[derive(Component)]
+pub struct ComboBarFill;
+
+/// HUD list of the run's `ActiveEffects`
+#[derive(Component)]
+pub struct StatusText;
+
+/// Marks enemies from a wave's special spawns, which play the role of the classic saucer
+#[derive(Component)]
+pub struct Saucer;
+
+/// Notification shown when an achievement is unlocked. It keeps its own clock
+/// instead of a `Lifetime`, so it fades on every screen, including the pause menu
+#[derive(Component, Default)]
+pub struct AchievementToast {
+    pub elapsed: f32,
+}
+
+/// Initials text on the name entry screen
+#[derive(Component)]
+pub struct InitialsText;
+
+/// List of actions and their bindings on the controls screen
+#[derive(Component)]
+pub struct BindingsText;
+
+/// Result of the last change on the controls screen
+#[derive(Component)]
+pub struct RebindStatusText;
+
+/// On-screen button: tapping or clicking it counts as its target
 #[derive(Component)]
-pub struct StartScreenUI;
+pub struct TapButton(pub TapTarget);
 
-/// Game over screen UI component marker
+/// Button bar shown during play while `PointerMode::Touch` is on
 #[derive(Component)]
-pub struct GameOverUI;
+pub struct TouchControls;
+
+/// Something a menu item does when it is confirmed or changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Resume the suspended run
+    Continue,
+    Play,
+    /// Switch between a standard run and the daily challenge
+    Mode,
+    Difficulty,
+    Settings,
+    HighScores,
+    Stats,
+    Achievements,
+    Controls,
+    Quit,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    ScreenShake,
+    StarfieldDensity,
+    GamepadDeadzone,
+    DisplayMode,
+    Autofire,
+    Language,
+    /// Start another run from the game over screen
+    Restart,
+    MainMenu,
+    /// Continue the paused run
+    Resume,
+    /// Save the paused run and return to the start screen
+    Suspend,
+    Back,
+}
+
+impl MenuAction {
+    /// Localization key of the item's label
+    pub fn key(self) -> &'static str {
+        match self {
+            MenuAction::Continue => "menu.continue",
+            MenuAction::Play => "menu.play",
+            MenuAction::Mode => "menu.mode",
+            MenuAction::Difficulty => "menu.difficulty",
+            MenuAction::Settings => "menu.settings",
+            MenuAction::HighScores => "menu.high_scores",
+            MenuAction::Stats => "menu.stats",
+            MenuAction::Achievements => "menu.achievements",
+            MenuAction::Controls => "menu.controls",
+            MenuAction::Quit => "menu.quit",
+            MenuAction::MasterVolume => "menu.master_volume",
+            MenuAction::MusicVolume => "menu.music_volume",
+            MenuAction::SfxVolume => "menu.sfx_volume",
+            MenuAction::ScreenShake => "menu.screen_shake",
+            MenuAction::StarfieldDensity => "menu.starfield",
+            MenuAction::GamepadDeadzone => "menu.gamepad_deadzone",
+            MenuAction::DisplayMode => "menu.display_mode",
+            MenuAction::Autofire => "menu.autofire",
+            MenuAction::Language => "menu.language",
+            MenuAction::Restart => "menu.restart",
+            MenuAction::MainMenu => "menu.main_menu",
+            MenuAction::Resume => "menu.resume",
+            MenuAction::Suspend => "menu.suspend",
+            MenuAction::Back => "menu.back",
+        }
+    }
+}
+
+/// Vertical list of selectable items, navigated with the keyboard, a gamepad,
+/// the mouse or touch. Its `MenuItem` children show the items.
+#[derive(Component)]
+pub struct Menu {
+    pub items: Vec<MenuAction>,
+    pub selected: usize,
+}
+
+impl Menu {
+    pub fn selected_action(&self) -> MenuAction {
+        self.items[self.selected]
+    }
+
+    /// Move the selection, wrapping around at either end of the list
+    pub fn move_selection(&mut self, step: i32) {
+        let count = self.items.len() as i32;
+        self.selected = (self.selected as i32 + step).rem_euclid(count) as usize;
+    }
+}
+
+/// Item of the parent `Menu`, at its index in `Menu::items`
+#[derive(Component)]
+pub struct MenuItem(pub usize);
+
+/// Text showing the localized string with this key, updated when the language
+/// changes
+#[derive(Component)]
+pub struct Localized(pub &'static str);
+
+/// Instructions showing the localized string with this key and the current
+/// menu bindings, updated when the language or the bindings change
+#[derive(Component)]
+pub struct BindingHint(pub &'static str);
 
 /// Star component for animated starfield background
 #[derive(Component)]
@@ -43,13 +242,112 @@ pub struct Star {
     pub distance_from_center: f32,
 }
 
-/// Explosion particle component
+/// Explosion particle component marker
+#[derive(Component)]
+pub struct ExplosionParticle;
+
+/// Despawns the entity, or returns it to its pool, once `elapsed` reaches `duration`
+#[derive(Component)]
+pub struct Lifetime {
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Lifetime {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    /// Fraction of the lifetime used up, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        (self.elapsed / self.duration).min(1.0)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// Despawns the entity, or returns it to its pool, once it is more than `margin`
+/// beyond the edge of the screen
 #[derive(Component)]
-pub struct ExplosionParticle {
-    pub lifetime: f32,
-    pub max_lifetime: f32,
+pub struct OffscreenDespawn {
+    pub margin: f32,
 }
 
+/// Entity pools an entity can belong to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolKind {
+    Star,
+    ExplosionParticle,
+    Bullet,
+    EnemyBullet,
+}
+
+/// Marks an entity owned by an entity pool, which is released instead of despawned
+#[derive(Component, Clone, Copy)]
+pub struct Pooled(pub PoolKind);
+
 /// Background music component marker
 #[derive(Component)]
 pub struct BackgroundMusic;
+
+/// Bit set of collision layers, used both for layer membership and masks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionLayer(u32);
+
+impl CollisionLayer {
+    pub const NONE: Self = Self(0);
+    pub const PLAYER: Self = Self(1 << 0);
+    pub const ENEMY: Self = Self(1 << 1);
+    pub const PLAYER_SHOT: Self = Self(1 << 2);
+    pub const ENEMY_SHOT: Self = Self(1 << 3);
+    pub const PICKUP: Self = Self(1 << 4);
+    pub const SHIELD: Self = Self(1 << 5);
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for CollisionLayer {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Axis-aligned rectangular hitbox with collision layer membership and mask.
+/// An entity reports a collision when its `mask` matches another hitbox's `layer`.
+#[derive(Component, Clone, Copy)]
+pub struct Hitbox {
+    pub half_size: Vec2,
+    pub layer: CollisionLayer,
+    pub mask: CollisionLayer,
+}
+
+impl Hitbox {
+    pub fn new(size: Vec2, layer: CollisionLayer, mask: CollisionLayer) -> Self {
+        Self {
+            half_size: size / 2.0,
+            layer,
+            mask,
+        }
+    }
+
+    /// Shrink the box on every side so transparent sprite edges don't collide
+    pub fn with_shrink(mut self, amount: f32) -> Self {
+        self.half_size = (self.half_size - Vec2::splat(amount)).max(Vec2::ZERO);
+        self
+    }
+
+    pub fn overlaps(&self, position: Vec2, other: &Hitbox, other_position: Vec2) -> bool {
+        let delta = (position - other_position).abs();
+        delta.x < self.half_size.x + other.half_size.x
+            && delta.y < self.half_size.y + other.half_size.y
+    }
+}
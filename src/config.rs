@@ -0,0 +1,303 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::constants::*;
+use crate::daily::{FNV_OFFSET_BASIS, fnv1a};
+
+/// Path of the game configuration asset, relative to `resources/`
+pub const GAME_CONFIG_PATH: &str = "game.config.ron";
+
+/// Tunable gameplay values, loaded from `resources/game.config.ron`.
+/// Missing fields fall back to the defaults in `constants.rs`.
+#[derive(Asset, Resource, TypePath, Clone, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub player: PlayerConfig,
+    pub bullet: BulletConfig,
+    pub enemy: EnemyConfig,
+    pub starfield: StarfieldConfig,
+    pub explosion: ExplosionConfig,
+    pub pools: PoolConfig,
+    pub high_scores: HighScoreConfig,
+    /// Hash of the file the config was loaded from, 0 for the built-in defaults
+    #[serde(skip)]
+    pub fingerprint: u64,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub speed: f32,
+    /// Seconds between shots while fire is held with autofire on
+    pub autofire_interval: f32,
+    /// Ships a run starts with, shared by every local player
+    pub lives: u32,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            speed: PLAYER_SPEED,
+            autofire_interval: PLAYER_AUTOFIRE_INTERVAL,
+            lives: PLAYER_LIVES,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletConfig {
+    pub speed: f32,
+}
+
+impl Default for BulletConfig {
+    fn default() -> Self {
+        Self {
+            speed: BULLET_SPEED,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyConfig {
+    pub speed: f32,
+    pub rows: usize,
+    pub cols: usize,
+    pub spacing: f32,
+    pub shoot_interval: f32,
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            speed: ENEMY_SPEED,
+            rows: ENEMY_ROWS,
+            cols: ENEMY_COLS,
+            spacing: ENEMY_SPACING,
+            shoot_interval: ENEMY_SHOOT_INTERVAL,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StarfieldConfig {
+    pub spawn_interval: f32,
+    pub base_speed: f32,
+    pub min_size: f32,
+    pub max_size: f32,
+    pub min_brightness: f32,
+    pub max_brightness: f32,
+}
+
+impl Default for StarfieldConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: STAR_SPAWN_INTERVAL,
+            base_speed: STAR_BASE_SPEED,
+            min_size: STAR_MIN_SIZE,
+            max_size: STAR_MAX_SIZE,
+            min_brightness: STAR_MIN_BRIGHTNESS,
+            max_brightness: STAR_MAX_BRIGHTNESS,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExplosionConfig {
+    pub particles: usize,
+    pub lifetime: f32,
+    pub particle_speed: f32,
+    pub particle_size: f32,
+    pub particle_drag: f32,
+    pub particle_gravity: f32,
+}
+
+impl Default for ExplosionConfig {
+    fn default() -> Self {
+        Self {
+            particles: EXPLOSION_PARTICLES,
+            lifetime: EXPLOSION_LIFETIME,
+            particle_speed: EXPLOSION_PARTICLE_SPEED,
+            particle_size: EXPLOSION_PARTICLE_SIZE,
+            particle_drag: EXPLOSION_PARTICLE_DRAG,
+            particle_gravity: EXPLOSION_PARTICLE_GRAVITY,
+        }
+    }
+}
+
+/// Number of pre-spawned entities kept for recycling per pooled entity type
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolConfig {
+    pub stars: usize,
+    pub explosion_particles: usize,
+    pub bullets: usize,
+    pub enemy_bullets: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            stars: STAR_POOL_SIZE,
+            explosion_particles: EXPLOSION_PARTICLE_POOL_SIZE,
+            bullets: BULLET_POOL_SIZE,
+            enemy_bullets: ENEMY_BULLET_POOL_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HighScoreConfig {
+    pub table_size: usize,
+}
+
+impl Default for HighScoreConfig {
+    fn default() -> Self {
+        Self {
+            table_size: HIGH_SCORE_TABLE_SIZE,
+        }
+    }
+}
+
+/// Errors raised while loading the game configuration
+#[derive(Debug, Error)]
+pub enum GameConfigError {
+    #[error("could not read game config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse game config: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("invalid game config: `{field}` {reason}")]
+    Invalid {
+        field: &'static str,
+        reason: &'static str,
+    },
+}
+
+impl GameConfig {
+    /// Check that every value is usable by the game systems
+    pub fn validate(&self) -> Result<(), GameConfigError> {
+        let positive = [
+            ("player.speed", self.player.speed),
+            ("player.autofire_interval", self.player.autofire_interval),
+            ("bullet.speed", self.bullet.speed),
+            ("enemy.speed", self.enemy.speed),
+            ("enemy.spacing", self.enemy.spacing),
+            ("enemy.shoot_interval", self.enemy.shoot_interval),
+            ("starfield.spawn_interval", self.starfield.spawn_interval),
+            ("starfield.base_speed", self.starfield.base_speed),
+            ("starfield.min_size", self.starfield.min_size),
+            ("explosion.lifetime", self.explosion.lifetime),
+            ("explosion.particle_speed", self.explosion.particle_speed),
+            ("explosion.particle_size", self.explosion.particle_size),
+        ];
+        for (field, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(GameConfigError::Invalid {
+                    field,
+                    reason: "must be a positive number",
+                });
+            }
+        }
+
+        if !(self.explosion.particle_drag.is_finite() && self.explosion.particle_drag >= 0.0) {
+            return Err(GameConfigError::Invalid {
+                field: "explosion.particle_drag",
+                reason: "must not be negative",
+            });
+        }
+        if !self.explosion.particle_gravity.is_finite() {
+            return Err(GameConfigError::Invalid {
+                field: "explosion.particle_gravity",
+                reason: "must be a number",
+            });
+        }
+
+        if self.player.lives == 0 {
+            return Err(GameConfigError::Invalid {
+                field: "player.lives",
+                reason: "must be at least 1",
+            });
+        }
+        if self.enemy.rows == 0 || self.enemy.cols == 0 {
+            return Err(GameConfigError::Invalid {
+                field: "enemy.rows/enemy.cols",
+                reason: "must be at least 1",
+            });
+        }
+        if self.high_scores.table_size == 0 {
+            return Err(GameConfigError::Invalid {
+                field: "high_scores.table_size",
+                reason: "must be at least 1",
+            });
+        }
+        if self.high_scores.table_size > HIGH_SCORE_TABLE_MAX_SIZE {
+            return Err(GameConfigError::Invalid {
+                field: "high_scores.table_size",
+                reason: "must be at most 10 to fit on screen",
+            });
+        }
+        if self.starfield.max_size < self.starfield.min_size {
+            return Err(GameConfigError::Invalid {
+                field: "starfield.max_size",
+                reason: "must not be smaller than starfield.min_size",
+            });
+        }
+
+        let brightness = [
+            ("starfield.min_brightness", self.starfield.min_brightness),
+            ("starfield.max_brightness", self.starfield.max_brightness),
+        ];
+        for (field, value) in brightness {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(GameConfigError::Invalid {
+                    field,
+                    reason: "must be between 0.0 and 1.0",
+                });
+            }
+        }
+        if self.starfield.max_brightness < self.starfield.min_brightness {
+            return Err(GameConfigError::Invalid {
+                field: "starfield.max_brightness",
+                reason: "must not be smaller than starfield.min_brightness",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Asset loader for `*.config.ron` files
+#[derive(Default, TypePath)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = GameConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GameConfig, GameConfigError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut config: GameConfig = ron::de::from_bytes(&bytes)?;
+        config.validate()?;
+        config.fingerprint = fnv1a(FNV_OFFSET_BASIS, &bytes);
+
+        Ok(config)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
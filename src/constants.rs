@@ -1,10 +1,21 @@
 // Window and display constants
-pub const WINDOW_WIDTH: f32 = 800.0;
-pub const WINDOW_HEIGHT: f32 = 600.0;
+/// Size of the playfield in world units, scaled to fit the window
+pub const PLAYFIELD_WIDTH: f32 = 800.0;
+pub const PLAYFIELD_HEIGHT: f32 = 600.0;
+/// Initial window size
+pub const WINDOW_WIDTH: f32 = PLAYFIELD_WIDTH;
+pub const WINDOW_HEIGHT: f32 = PLAYFIELD_HEIGHT;
+
+// Simulation constants
+/// Gameplay ticks per second. Runs advance in ticks of this fixed length,
+/// whatever the frame rate, so a daily challenge replays from its input log.
+pub const GAMEPLAY_TICK_RATE: f64 = 64.0;
 
 // Player constants
 pub const PLAYER_SIZE: f32 = 40.0;
 pub const PLAYER_SPEED: f32 = 300.0;
+pub const PLAYER_AUTOFIRE_INTERVAL: f32 = 0.25;
+pub const PLAYER_LIVES: u32 = 3;
 
 // Bullet constants
 pub const BULLET_SIZE: f32 = 5.0;
@@ -31,3 +42,55 @@ pub const EXPLOSION_PARTICLES: usize = 20;
 pub const EXPLOSION_LIFETIME: f32 = 0.8;
 pub const EXPLOSION_PARTICLE_SPEED: f32 = 200.0;
 pub const EXPLOSION_PARTICLE_SIZE: f32 = 3.0;
+pub const EXPLOSION_PARTICLE_DRAG: f32 = 0.0;
+pub const EXPLOSION_PARTICLE_GRAVITY: f32 = 0.0;
+
+// Entity pool constants
+pub const STAR_POOL_SIZE: usize = 256;
+pub const EXPLOSION_PARTICLE_POOL_SIZE: usize = 512;
+pub const BULLET_POOL_SIZE: usize = 32;
+pub const ENEMY_BULLET_POOL_SIZE: usize = 64;
+
+// Collision constants
+pub const COLLISION_GRID_CELL_SIZE: f32 = 64.0;
+pub const PLAYER_HITBOX_SHRINK: f32 = 4.0;
+pub const ENEMY_HITBOX_SHRINK: f32 = 2.0;
+
+// Achievement constants
+pub const ACHIEVEMENT_TOAST_DURATION: f32 = 3.0;
+
+// Scoring constants
+pub const ENEMY_POINTS: u32 = 10;
+pub const HIGH_SCORE_TABLE_SIZE: usize = 5;
+/// Largest table that stays readable once its rows are squeezed between the
+/// heading and the menu of the game over screen
+pub const HIGH_SCORE_TABLE_MAX_SIZE: usize = 10;
+pub const HIGH_SCORE_ROW_HEIGHT: f32 = 32.0;
+pub const DAILY_TABLE_SIZE: usize = 5;
+
+// Input constants
+pub const MAX_LOCAL_PLAYERS: usize = 2;
+/// Height of the on-screen button bar shown for touch input; drags that start
+/// on it do not move the ship
+pub const TOUCH_BAR_HEIGHT: f32 = 90.0;
+
+// Settings defaults
+pub const DEFAULT_MASTER_VOLUME: f32 = 1.0;
+pub const DEFAULT_MUSIC_VOLUME: f32 = 0.7;
+pub const DEFAULT_SFX_VOLUME: f32 = 1.0;
+pub const DEFAULT_SCREEN_SHAKE: f32 = 1.0;
+pub const DEFAULT_STARFIELD_DENSITY: f32 = 1.0;
+pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.2;
+
+// HUD constants
+pub const HUD_PADDING: f32 = 10.0;
+pub const HUD_LIFE_ICON_SIZE: f32 = 24.0;
+pub const HUD_COMBO_BAR_WIDTH: f32 = 120.0;
+pub const HUD_COMBO_BAR_HEIGHT: f32 = 6.0;
+pub const COMBO_WINDOW: f32 = 2.0;
+
+// Screen shake constants
+pub const SCREEN_SHAKE_MAX_OFFSET: f32 = 12.0;
+pub const SCREEN_SHAKE_DECAY: f32 = 2.5;
+pub const SCREEN_SHAKE_PLAYER_HIT: f32 = 0.8;
+pub const SCREEN_SHAKE_ENEMY_KILLED: f32 = 0.15;
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::constants::DAILY_TABLE_SIZE;
+use crate::resources::TickInput;
+use crate::storage::Persistent;
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash, continuing from `hash`. Stable across platforms and
+/// releases, unlike the standard library's hashers.
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Kind of run being played
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunMode {
+    #[default]
+    Standard,
+    /// The daily challenge of the given local date
+    Daily(NaiveDate),
+}
+
+/// Seed of the gameplay RNG for the daily challenge of `date`, the same for every player
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    fnv1a(
+        FNV_OFFSET_BASIS,
+        date.format("%Y-%m-%d").to_string().as_bytes(),
+    )
+}
+
+/// Player input of a daily run, one `TickInput` per gameplay tick, kept as
+/// spans of identical ticks. Replayed from the daily seed it plays the run
+/// again, which is how `replay::verify` checks the score of a code.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputLog {
+    spans: Vec<InputSpan>,
+}
+
+/// `ticks` consecutive gameplay ticks with the same input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputSpan {
+    pub input: TickInput,
+    pub ticks: u32,
+}
+
+impl InputLog {
+    /// Add the input of one tick
+    pub fn record(&mut self, input: TickInput) {
+        match self.spans.last_mut() {
+            Some(span) if span.input == input => span.ticks += 1,
+            _ => self.spans.push(InputSpan { input, ticks: 1 }),
+        }
+    }
+
+    /// Number of ticks recorded
+    pub fn ticks(&self) -> u32 {
+        self.spans.iter().map(|span| span.ticks).sum()
+    }
+
+    /// Input of every tick, in order
+    pub fn inputs(&self) -> impl Iterator<Item = TickInput> + '_ {
+        self.spans
+            .iter()
+            .flat_map(|span| std::iter::repeat_n(span.input, span.ticks as usize))
+    }
+
+    /// Hash of the input of every tick, as written in a daily code
+    pub fn hash(&self) -> u64 {
+        self.inputs().fold(FNV_OFFSET_BASIS, |hash, input| {
+            let [low, high] = input.steering.to_le_bytes();
+            let buttons = u8::from(input.fire) | u8::from(input.autofire) << 1;
+            fnv1a(hash, &[low, high, buttons])
+        })
+    }
+}
+
+/// Input log being replayed in place of the player's controls, see `replay::verify`
+#[derive(Resource)]
+pub struct InputReplay {
+    /// Seed of the gameplay RNG for the run
+    pub seed: u64,
+    inputs: std::vec::IntoIter<TickInput>,
+}
+
+impl InputReplay {
+    pub fn new(seed: u64, input_log: &InputLog) -> Self {
+        Self {
+            seed,
+            inputs: input_log.inputs().collect::<Vec<_>>().into_iter(),
+        }
+    }
+
+    /// Input of the next tick, or `None` once the log is used up
+    pub fn next_input(&mut self) -> Option<TickInput> {
+        self.inputs.next()
+    }
+
+    /// Whether every tick of the log has been replayed
+    pub fn finished(&self) -> bool {
+        self.inputs.len() == 0
+    }
+}
+
+/// Shareable summary of a daily run: `VI-<seed>-<score>-<input hash>-<checksum>`.
+/// The checksum only catches typos: anyone can compute it. The score is proven
+/// by replaying the run's input log with `replay::verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyCode {
+    pub seed: u64,
+    pub score: u32,
+    pub input_hash: u64,
+}
+
+/// Errors raised while reading a daily challenge code
+#[derive(Debug, Error)]
+pub enum DailyCodeError {
+    #[error("not a daily challenge code")]
+    Format,
+    #[error("invalid number in daily challenge code: {0}")]
+    Number(#[from] ParseIntError),
+    #[error("daily challenge code checksum does not match")]
+    Checksum,
+    #[error("input log does not match the daily challenge code")]
+    InputLog,
+    #[error("replaying the input log does not end the run")]
+    Unfinished,
+    #[error("replaying the input log scores {0}, not the score of the code")]
+    Score(u32),
+}
+
+impl DailyCode {
+    /// Code for a daily challenge of `date` that ended with `score`
+    pub fn new(date: NaiveDate, score: u32, input_log: &InputLog) -> Self {
+        Self {
+            seed: daily_seed(date),
+            score,
+            input_hash: input_log.hash(),
+        }
+    }
+
+    fn body(&self) -> String {
+        format!(
+            "VI-{:016X}-{}-{:016X}",
+            self.seed, self.score, self.input_hash
+        )
+    }
+
+    fn checksum(body: &str) -> u32 {
+        let hash = fnv1a(FNV_OFFSET_BASIS, body.as_bytes());
+        (hash ^ (hash >> 32)) as u32
+    }
+}
+
+impl fmt::Display for DailyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = self.body();
+        write!(f, "{body}-{:08X}", Self::checksum(&body))
+    }
+}
+
+impl FromStr for DailyCode {
+    type Err = DailyCodeError;
+
+    fn from_str(code: &str) -> Result<Self, DailyCodeError> {
+        let code = code.trim();
+        let (body, checksum) = code.rsplit_once('-').ok_or(DailyCodeError::Format)?;
+        let fields: Vec<&str> = body.split('-').collect();
+        let ["VI", seed, score, input_hash] = fields[..] else {
+            return Err(DailyCodeError::Format);
+        };
+
+        let parsed = DailyCode {
+            seed: u64::from_str_radix(seed, 16)?,
+            score: score.parse()?,
+            input_hash: u64::from_str_radix(input_hash, 16)?,
+        };
+        if u32::from_str_radix(checksum, 16)? != Self::checksum(&parsed.body()) {
+            return Err(DailyCodeError::Checksum);
+        }
+        Ok(parsed)
+    }
+}
+
+/// Result of one daily challenge
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub date: NaiveDate,
+    pub score: u32,
+    /// Wave reached, counted from 1
+    pub wave: usize,
+    pub code: String,
+    /// Input of the run, to check `code` with `replay::verify`
+    #[serde(default)]
+    pub input_log: InputLog,
+    /// Set for the result of the run that just ended; never saved
+    #[serde(skip)]
+    pub is_current: bool,
+}
+
+/// Best daily challenge results, kept apart from `HighScores` and saved between sessions
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyScores {
+    pub scores: Vec<DailyEntry>,
+    /// Date of the last daily challenge started; there is one attempt per day
+    pub last_attempt: Option<NaiveDate>,
+}
+
+impl Persistent for DailyScores {
+    const KEY: &'static str = "daily_scores";
+    const VERSION: u32 = 1;
+}
+
+impl DailyScores {
+    pub fn attempted(&self, date: NaiveDate) -> bool {
+        self.last_attempt == Some(date)
+    }
+
+    /// Use up the attempt of `date`, returning `false` if it was already used
+    pub fn start(&mut self, date: NaiveDate) -> bool {
+        if self.attempted(date) {
+            return false;
+        }
+        self.last_attempt = Some(date);
+        true
+    }
+
+    /// Add the result of the run that just ended, marking it as current
+    pub fn add(&mut self, entry: DailyEntry) {
+        for score in &mut self.scores {
+            score.is_current = false;
+        }
+        self.scores.push(DailyEntry {
+            is_current: true,
+            ..entry
+        });
+        self.scores
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.scores.truncate(DAILY_TABLE_SIZE);
+    }
+}
@@ -0,0 +1,440 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::locale::Localization;
+
+/// Something the player can do, bound to any number of inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    /// Pause and resume the run
+    Pause,
+    Confirm,
+    Back,
+    MenuUp,
+    MenuDown,
+    /// Change the selected menu item, or move left in a list
+    MenuLeft,
+    /// Change the selected menu item, or move right in a list
+    MenuRight,
+}
+
+/// Where an action is used. Actions of different contexts may share inputs,
+/// such as Space for both Fire and Confirm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionContext {
+    Gameplay,
+    Menu,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Pause,
+        Action::Confirm,
+        Action::Back,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+    ];
+
+    /// Localization key of the action's name
+    pub fn key(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::Fire => "action.fire",
+            Action::Pause => "action.pause",
+            Action::Confirm => "action.confirm",
+            Action::Back => "action.back",
+            Action::MenuUp => "action.menu_up",
+            Action::MenuDown => "action.menu_down",
+            Action::MenuLeft => "action.menu_left",
+            Action::MenuRight => "action.menu_right",
+        }
+    }
+
+    pub fn context(self) -> ActionContext {
+        match self {
+            Action::MoveLeft | Action::MoveRight | Action::Fire | Action::Pause => {
+                ActionContext::Gameplay
+            }
+            Action::Confirm
+            | Action::Back
+            | Action::MenuUp
+            | Action::MenuDown
+            | Action::MenuLeft
+            | Action::MenuRight => ActionContext::Menu,
+        }
+    }
+}
+
+/// What an on-screen button stands in for when tapped or clicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapTarget {
+    Action(Action),
+}
+
+/// One input that can trigger an action. Saved by name: keys by their `KeyCode`
+/// variant (`"KeyA"`), other inputs with a prefix (`"Mouse:Left"`,
+/// `"Pad:South"`, `"Pad:LeftStickX-"`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// One direction of a stick axis, past the gamepad deadzone
+    Stick {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+/// Declare the keys that can be bound, named after their `KeyCode` variant
+macro_rules! bindable_keys {
+    ($($key:ident),* $(,)?) => {
+        const BINDABLE_KEYS: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+    };
+}
+
+bindable_keys! {
+    KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO, KeyP,
+    KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ, Digit0, Digit1, Digit2, Digit3,
+    Digit4, Digit5, Digit6, Digit7, Digit8, Digit9, ArrowLeft, ArrowRight, ArrowUp, ArrowDown,
+    Space, Enter, Tab, Backspace, Escape, ShiftLeft, ShiftRight, ControlLeft, ControlRight,
+    AltLeft, AltRight, Comma, Period, Slash, Semicolon, Quote, BracketLeft, BracketRight, Minus,
+    Equal, Backquote, Backslash, Home, End, PageUp, PageDown, Insert, Delete, Numpad0, Numpad1,
+    Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9, NumpadEnter,
+}
+
+/// Mouse buttons that can be bound: button, saved name
+const BINDABLE_MOUSE_BUTTONS: &[(MouseButton, &str)] = &[
+    (MouseButton::Left, "Left"),
+    (MouseButton::Right, "Right"),
+    (MouseButton::Middle, "Middle"),
+];
+
+/// Gamepad buttons that can be bound: button, saved name
+const BINDABLE_GAMEPAD_BUTTONS: &[(GamepadButton, &str)] = &[
+    (GamepadButton::South, "South"),
+    (GamepadButton::East, "East"),
+    (GamepadButton::West, "West"),
+    (GamepadButton::North, "North"),
+    (GamepadButton::LeftTrigger, "LeftTrigger"),
+    (GamepadButton::RightTrigger, "RightTrigger"),
+    (GamepadButton::LeftTrigger2, "LeftTrigger2"),
+    (GamepadButton::RightTrigger2, "RightTrigger2"),
+    (GamepadButton::Select, "Select"),
+    (GamepadButton::Start, "Start"),
+    (GamepadButton::LeftThumb, "LeftThumb"),
+    (GamepadButton::RightThumb, "RightThumb"),
+    (GamepadButton::DPadUp, "DPadUp"),
+    (GamepadButton::DPadDown, "DPadDown"),
+    (GamepadButton::DPadLeft, "DPadLeft"),
+    (GamepadButton::DPadRight, "DPadRight"),
+];
+
+/// Stick axes that can be bound: axis, saved name
+const BINDABLE_STICK_AXES: &[(GamepadAxis, &str)] = &[
+    (GamepadAxis::LeftStickX, "LeftStickX"),
+    (GamepadAxis::LeftStickY, "LeftStickY"),
+    (GamepadAxis::RightStickX, "RightStickX"),
+    (GamepadAxis::RightStickY, "RightStickY"),
+];
+
+/// Stick axes that can be bound, for capturing a new binding
+pub fn bindable_stick_axes() -> impl Iterator<Item = GamepadAxis> {
+    BINDABLE_STICK_AXES.iter().map(|(axis, ..)| *axis)
+}
+
+/// Name a key is saved under, or `None` if it cannot be bound
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    BINDABLE_KEYS
+        .iter()
+        .find(|(bindable, _)| *bindable == key)
+        .map(|(_, name)| *name)
+}
+
+/// Key saved under `name`
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .find(|(_, bindable)| *bindable == name)
+        .map(|(key, _)| *key)
+}
+
+impl Binding {
+    /// Name the binding is saved under, or `None` if it cannot be bound
+    pub fn name(self) -> Option<String> {
+        match self {
+            Binding::Key(key) => key_name(key).map(str::to_string),
+            Binding::Mouse(button) => BINDABLE_MOUSE_BUTTONS
+                .iter()
+                .find(|(bindable, _)| *bindable == button)
+                .map(|(_, name)| format!("Mouse:{name}")),
+            Binding::Gamepad(button) => BINDABLE_GAMEPAD_BUTTONS
+                .iter()
+                .find(|(bindable, _)| *bindable == button)
+                .map(|(_, name)| format!("Pad:{name}")),
+            Binding::Stick { axis, positive } => BINDABLE_STICK_AXES
+                .iter()
+                .find(|(bindable, _)| *bindable == axis)
+                .map(|(_, name)| format!("Pad:{name}{}", if positive { '+' } else { '-' })),
+        }
+    }
+
+    /// Binding saved under `name`
+    pub fn from_name(name: &str) -> Option<Binding> {
+        if let Some(name) = name.strip_prefix("Mouse:") {
+            return BINDABLE_MOUSE_BUTTONS
+                .iter()
+                .find(|(_, bindable)| *bindable == name)
+                .map(|(button, _)| Binding::Mouse(*button));
+        }
+        if let Some(name) = name.strip_prefix("Pad:") {
+            if let Some((axis_name, positive)) = name
+                .strip_suffix('+')
+                .map(|axis| (axis, true))
+                .or_else(|| name.strip_suffix('-').map(|axis| (axis, false)))
+            {
+                return BINDABLE_STICK_AXES
+                    .iter()
+                    .find(|(_, bindable)| *bindable == axis_name)
+                    .map(|(axis, _)| Binding::Stick {
+                        axis: *axis,
+                        positive,
+                    });
+            }
+            return BINDABLE_GAMEPAD_BUTTONS
+                .iter()
+                .find(|(_, bindable)| *bindable == name)
+                .map(|(button, _)| Binding::Gamepad(*button));
+        }
+        key_from_name(name).map(Binding::Key)
+    }
+
+    /// Short text shown to players in the current language, e.g. `"A"`,
+    /// `"Left"`, `"Pad A"`, `"Left Stick Left"`. Looked up as `input.<name>`,
+    /// with the `:` of the saved name replaced by `.`; keys without a string,
+    /// such as letters and digits, show their own character.
+    pub fn label(self, localization: &Localization) -> String {
+        let Some(name) = self.name() else {
+            return match self {
+                Binding::Key(key) => format!("{key:?}"),
+                Binding::Mouse(button) => format!("{button:?}"),
+                Binding::Gamepad(button) => format!("{button:?}"),
+                Binding::Stick { axis, .. } => format!("{axis:?}"),
+            };
+        };
+        let key = format!("input.{}", name.replace(':', "."));
+        match self {
+            Binding::Key(_) => {
+                let shown = ["Key", "Digit"]
+                    .iter()
+                    .find_map(|prefix| name.strip_prefix(prefix))
+                    .unwrap_or(&name);
+                localization.text_or(&key, shown)
+            }
+            _ => localization.text(&key),
+        }
+    }
+}
+
+impl Serialize for Binding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error as _;
+
+        self.name()
+            .ok_or_else(|| S::Error::custom(format!("{self:?} cannot be bound")))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Binding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Binding, D::Error> {
+        use serde::de::Error as _;
+
+        let name = String::deserialize(deserializer)?;
+        Binding::from_name(&name).ok_or_else(|| D::Error::custom(format!("unknown input {name:?}")))
+    }
+}
+
+/// Inputs bound to each action. Field names match the key-only bindings saved
+/// by earlier versions, which still load.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionBindings {
+    pub move_left: Vec<Binding>,
+    pub move_right: Vec<Binding>,
+    pub fire: Vec<Binding>,
+    pub pause: Vec<Binding>,
+    pub confirm: Vec<Binding>,
+    pub back: Vec<Binding>,
+    pub menu_up: Vec<Binding>,
+    pub menu_down: Vec<Binding>,
+    pub menu_left: Vec<Binding>,
+    pub menu_right: Vec<Binding>,
+}
+
+impl Default for ActionBindings {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key, Stick};
+
+        Self {
+            move_left: vec![
+                Key(KeyCode::ArrowLeft),
+                Key(KeyCode::KeyA),
+                Gamepad(GamepadButton::DPadLeft),
+                Stick {
+                    axis: GamepadAxis::LeftStickX,
+                    positive: false,
+                },
+            ],
+            move_right: vec![
+                Key(KeyCode::ArrowRight),
+                Key(KeyCode::KeyD),
+                Gamepad(GamepadButton::DPadRight),
+                Stick {
+                    axis: GamepadAxis::LeftStickX,
+                    positive: true,
+                },
+            ],
+            fire: vec![
+                Key(KeyCode::Space),
+                Gamepad(GamepadButton::South),
+                Gamepad(GamepadButton::East),
+                Gamepad(GamepadButton::North),
+                Gamepad(GamepadButton::West),
+            ],
+            pause: vec![Key(KeyCode::Escape), Gamepad(GamepadButton::Start)],
+            confirm: vec![Key(KeyCode::Space), Gamepad(GamepadButton::South)],
+            back: vec![
+                Key(KeyCode::Escape),
+                Key(KeyCode::Backspace),
+                Gamepad(GamepadButton::East),
+            ],
+            menu_up: vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)],
+            menu_down: vec![Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)],
+            menu_left: vec![Key(KeyCode::ArrowLeft), Gamepad(GamepadButton::DPadLeft)],
+            menu_right: vec![Key(KeyCode::ArrowRight), Gamepad(GamepadButton::DPadRight)],
+        }
+    }
+}
+
+/// Why a binding could not be added
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BindError {
+    /// The input is already bound to this action
+    AlreadyBound,
+    /// The input is bound to another action used at the same time
+    Conflict(Action),
+    /// The input has no saved name, so the binding could not be kept
+    Unbindable,
+}
+
+impl ActionBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match action {
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Fire => &self.fire,
+            Action::Pause => &self.pause,
+            Action::Confirm => &self.confirm,
+            Action::Back => &self.back,
+            Action::MenuUp => &self.menu_up,
+            Action::MenuDown => &self.menu_down,
+            Action::MenuLeft => &self.menu_left,
+            Action::MenuRight => &self.menu_right,
+        }
+    }
+
+    fn get_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Fire => &mut self.fire,
+            Action::Pause => &mut self.pause,
+            Action::Confirm => &mut self.confirm,
+            Action::Back => &mut self.back,
+            Action::MenuUp => &mut self.menu_up,
+            Action::MenuDown => &mut self.menu_down,
+            Action::MenuLeft => &mut self.menu_left,
+            Action::MenuRight => &mut self.menu_right,
+        }
+    }
+
+    /// Another action of the same context that `binding` already triggers
+    pub fn conflict(&self, action: Action, binding: Binding) -> Option<Action> {
+        Action::ALL.into_iter().find(|other| {
+            *other != action
+                && other.context() == action.context()
+                && self.get(*other).contains(&binding)
+        })
+    }
+
+    /// Add `binding` to `action`, unless it would also trigger another action
+    pub fn bind(&mut self, action: Action, binding: Binding) -> Result<(), BindError> {
+        if binding.name().is_none() {
+            return Err(BindError::Unbindable);
+        }
+        if self.get(action).contains(&binding) {
+            return Err(BindError::AlreadyBound);
+        }
+        if let Some(other) = self.conflict(action, binding) {
+            return Err(BindError::Conflict(other));
+        }
+        self.get_mut(action).push(binding);
+        Ok(())
+    }
+
+    /// Remove the most recently added binding of `action`, keeping at least one
+    /// so the action stays usable. Returns the removed binding.
+    pub fn unbind_last(&mut self, action: Action) -> Option<Binding> {
+        let bindings = self.get_mut(action);
+        if bindings.len() > 1 {
+            bindings.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Labels of every binding of `action`, e.g. `"Left, A, D-Pad Left"`
+    pub fn describe(&self, action: Action, localization: &Localization) -> String {
+        self.get(action)
+            .iter()
+            .map(|binding| binding.label(localization))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The string `key` with `{confirm}`, `{back}`, `{up}`, `{down}`, `{left}`
+    /// and `{right}` filled in with the inputs bound to the menu actions, so
+    /// instructions follow the player's bindings
+    pub fn hint(&self, key: &str, localization: &Localization) -> String {
+        let [confirm, back, up, down, left, right] = [
+            Action::Confirm,
+            Action::Back,
+            Action::MenuUp,
+            Action::MenuDown,
+            Action::MenuLeft,
+            Action::MenuRight,
+        ]
+        .map(|action| self.describe(action, localization));
+        localization.format(
+            key,
+            &[
+                ("confirm", &confirm),
+                ("back", &back),
+                ("up", &up),
+                ("down", &down),
+                ("left", &left),
+                ("right", &right),
+            ],
+        )
+    }
+}
@@ -0,0 +1,20 @@
+//! Vibe Invaders game logic: components, resources, messages and systems.
+//! `plugins` wires them into the schedule, both for the binary in `main.rs`
+//! and for the integration tests.
+
+pub mod achievements;
+pub mod components;
+pub mod config;
+pub mod constants;
+pub mod daily;
+pub mod input;
+pub mod locale;
+pub mod messages;
+pub mod plugins;
+pub mod replay;
+pub mod resources;
+pub mod settings;
+pub mod snapshot;
+pub mod storage;
+pub mod systems;
+pub mod waves;
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::components::Localized;
+
+/// A language the game can be played in
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// Code saved in the settings and used to name the translation file
+    pub code: &'static str,
+    /// Name of the language in that language, shown on the settings screen
+    pub name: &'static str,
+}
+
+/// Language used until another one is picked
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Languages with a translation in `resources/locales/`, in the order the
+/// settings screen offers them
+pub const LANGUAGES: [Language; 4] = [
+    Language {
+        code: "en",
+        name: "English",
+    },
+    Language {
+        code: "de",
+        name: "Deutsch",
+    },
+    Language {
+        code: "es",
+        name: "Español",
+    },
+    Language {
+        code: "ru",
+        name: "Русский",
+    },
+];
+
+/// English strings, built in so that every key has a text before the
+/// translation files have loaded and when a translation lacks one
+const ENGLISH: &str = include_str!("../resources/locales/en.locale.ron");
+
+impl Language {
+    pub fn from_code(code: &str) -> Option<&'static Language> {
+        LANGUAGES.iter().find(|language| language.code == code)
+    }
+
+    /// Path of the translation file, relative to `resources/`
+    pub fn path(&self) -> String {
+        format!("locales/{}.locale.ron", self.code)
+    }
+
+    /// The language `step` places further along `LANGUAGES` from `code`,
+    /// wrapping around. Unknown codes count as the first language.
+    pub fn cycle(code: &str, step: i32) -> &'static Language {
+        let len = LANGUAGES.len() as i32;
+        let index = LANGUAGES
+            .iter()
+            .position(|language| language.code == code)
+            .unwrap_or(0) as i32;
+        &LANGUAGES[(index + step).rem_euclid(len) as usize]
+    }
+}
+
+/// Plural forms a translation can give for a counted string, named after the
+/// CLDR plural categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum PluralCategory {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+/// How a language picks the plural form for a count
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum PluralRule {
+    /// 1 is singular, everything else plural (English, German, Spanish)
+    #[default]
+    OneOther,
+    /// 0 and 1 are singular (French, Brazilian Portuguese)
+    ZeroOneOther,
+    /// A single form for every count (Japanese, Chinese, Korean)
+    Invariant,
+    /// One, Few and Many by the last digits (Russian, Ukrainian)
+    EastSlavic,
+}
+
+impl PluralRule {
+    pub fn category(self, count: u64) -> PluralCategory {
+        match self {
+            PluralRule::OneOther if count == 1 => PluralCategory::One,
+            PluralRule::ZeroOneOther if count <= 1 => PluralCategory::One,
+            PluralRule::OneOther | PluralRule::ZeroOneOther | PluralRule::Invariant => {
+                PluralCategory::Other
+            }
+            PluralRule::EastSlavic => match (count % 10, count % 100) {
+                (1, tens) if tens != 11 => PluralCategory::One,
+                (2..=4, tens) if !(12..=14).contains(&tens) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            },
+        }
+    }
+}
+
+/// The UI text of one language, loaded from `resources/locales/*.locale.ron`.
+/// Strings may contain `{name}` placeholders filled in by `Localization`.
+#[derive(Asset, TypePath, Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Locale {
+    pub plural_rule: PluralRule,
+    /// Put between groups of three digits in large numbers
+    pub thousands_separator: String,
+    /// Fonts to try in order for this language's script, relative to
+    /// `resources/`; Bevy's built-in font is the last resort. The first one
+    /// that has loaded draws every text: there is no fallback per glyph, so it
+    /// needs every character the language shows, language names included.
+    pub fonts: Vec<String>,
+    pub strings: HashMap<String, String>,
+    /// Counted strings, with one text per plural form of the language
+    pub plurals: HashMap<String, HashMap<PluralCategory, String>>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            plural_rule: PluralRule::default(),
+            thousands_separator: ",".to_string(),
+            fonts: Vec::new(),
+            strings: HashMap::new(),
+            plurals: HashMap::new(),
+        }
+    }
+}
+
+/// Errors raised while loading a translation
+#[derive(Debug, Error)]
+pub enum LocaleError {
+    #[error("could not read translation: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse translation: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("plural string '{0}' has no Other form")]
+    MissingOther(String),
+}
+
+impl Locale {
+    pub fn parse(bytes: &[u8]) -> Result<Locale, LocaleError> {
+        let locale: Locale = ron::de::from_bytes(bytes)?;
+        locale.validate()?;
+        Ok(locale)
+    }
+
+    /// The built-in English strings
+    pub fn english() -> Locale {
+        Locale::parse(ENGLISH.as_bytes()).expect("built-in English strings are valid")
+    }
+
+    /// Check that every counted string has a form to fall back on
+    pub fn validate(&self) -> Result<(), LocaleError> {
+        match self
+            .plurals
+            .iter()
+            .find(|(_, forms)| !forms.contains_key(&PluralCategory::Other))
+        {
+            Some((key, _)) => Err(LocaleError::MissingOther(key.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn plural(&self, key: &str, count: u64) -> Option<&str> {
+        let forms = self.plurals.get(key)?;
+        forms
+            .get(&self.plural_rule.category(count))
+            .or_else(|| forms.get(&PluralCategory::Other))
+            .map(String::as_str)
+    }
+}
+
+/// Asset loader for `*.locale.ron` files
+#[derive(Default, TypePath)]
+pub struct LocaleLoader;
+
+impl AssetLoader for LocaleLoader {
+    type Asset = Locale;
+    type Settings = ();
+    type Error = LocaleError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Locale, LocaleError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Locale::parse(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+/// Fill in the `{name}` placeholders of `text`
+fn substitute(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+/// UI text in the language picked in the settings. Strings the translation
+/// lacks are shown in English, and keys without any string as the key itself.
+#[derive(Resource)]
+pub struct Localization {
+    /// Code of the language shown
+    pub language: String,
+    pub locale: Locale,
+    english: Locale,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        let english = Locale::english();
+        Self {
+            language: DEFAULT_LANGUAGE.to_string(),
+            locale: english.clone(),
+            english,
+        }
+    }
+}
+
+impl Localization {
+    /// Show the strings of `locale` for `language` from now on
+    pub fn set(&mut self, language: &str, locale: Locale) {
+        self.language = language.to_string();
+        self.locale = locale;
+    }
+
+    fn lookup(&self, key: &str) -> Option<&str> {
+        self.locale
+            .strings
+            .get(key)
+            .or_else(|| self.english.strings.get(key))
+            .map(String::as_str)
+    }
+
+    /// The string for `key`
+    pub fn text(&self, key: &str) -> String {
+        self.lookup(key).unwrap_or(key).to_string()
+    }
+
+    /// The string for `key`, or `default` when no language has one. Used for
+    /// text that comes with its own English, such as achievement names.
+    pub fn text_or(&self, key: &str, default: &str) -> String {
+        self.lookup(key).unwrap_or(default).to_string()
+    }
+
+    /// The string for `key` with its placeholders filled in
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        substitute(self.lookup(key).unwrap_or(key), args)
+    }
+
+    /// The form of the counted string `key` for `count`, with `{count}` and
+    /// the other placeholders filled in
+    pub fn plural(&self, key: &str, count: u64, args: &[(&str, &dyn Display)]) -> String {
+        let text = self
+            .locale
+            .plural(key, count)
+            .or_else(|| self.english.plural(key, count))
+            .unwrap_or(key);
+        let count = self.number(count);
+        substitute(text, &[&[("count", &count as &dyn Display)], args].concat())
+    }
+
+    /// `value` with its digits grouped in threes
+    pub fn number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(&self.locale.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// `fraction` as a whole percentage
+    pub fn percent(&self, fraction: f32) -> String {
+        let value = (fraction * 100.0).round();
+        self.format("format.percent", &[("value", &value)])
+    }
+
+    /// On or off, for toggles
+    pub fn on_off(&self, enabled: bool) -> String {
+        self.text(if enabled { "value.on" } else { "value.off" })
+    }
+
+    /// Text for `key` that follows language changes
+    pub fn localized(&self, key: &'static str) -> (Text, Localized) {
+        (Text::new(self.text(key)), Localized(key))
+    }
+
+    /// Fonts to try in order for the current language, then for English
+    pub fn fonts(&self) -> impl Iterator<Item = &str> {
+        self.locale
+            .fonts
+            .iter()
+            .chain(&self.english.fonts)
+            .map(String::as_str)
+    }
+}
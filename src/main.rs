@@ -1,14 +1,8 @@
-mod components;
-mod constants;
-mod resources;
-mod systems;
-
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
-use constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
-
-use resources::*;
-use systems::*;
+use vibe_invaders::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use vibe_invaders::plugins::*;
+use vibe_invaders::storage::Storage;
 
 fn main() {
     App::new()
@@ -17,11 +11,14 @@ fn main() {
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Vibe Invaders".to_string(),
-                        resizable: false,
-                        // Use windowed mode for WASM compatibility
+                        resizable: true,
+                        // Start windowed; apply_settings switches to the saved display mode
                         mode: bevy::window::WindowMode::Windowed,
                         // Set explicit resolution
-                        resolution: WindowResolution::new(WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32),
+                        resolution: WindowResolution::new(
+                            WINDOW_WIDTH as u32,
+                            WINDOW_HEIGHT as u32,
+                        ),
                         // WASM canvas configuration
                         canvas: Some("#bevy-canvas".to_string()),
                         fit_canvas_to_parent: true,
@@ -35,56 +32,12 @@ fn main() {
                     ..default()
                 }),
         )
-        .init_state::<GamePhase>()
-        .init_resource::<GameState>()
-        .init_resource::<WindowDimensions>()
-        .init_resource::<HighScores>()
-        .init_resource::<GameOverTimer>()
-        .add_systems(Startup, (setup, initialize_window_dimensions.after(setup)))
-        // Start screen systems
-        .add_systems(OnEnter(GamePhase::StartScreen), setup_start_screen)
-        .add_systems(
-            Update,
-            start_screen_input.run_if(in_state(GamePhase::StartScreen)),
-        )
-        .add_systems(OnExit(GamePhase::StartScreen), cleanup_start_screen)
-        // Game playing systems
-        .add_systems(OnEnter(GamePhase::Playing), (spawn_player, spawn_enemies))
-        .add_systems(
-            Update,
-            (
-                // Background systems
-                spawn_stars,
-                move_stars,
-                // Explosion system
-                update_explosions,
-                // Player systems
-                player_movement,
-                player_shoot,
-                // Bullet systems
-                move_bullets,
-                move_enemy_bullets,
-                cleanup_offscreen_bullets,
-                // Enemy systems
-                move_enemies,
-                enemy_shoot,
-                // Collision systems
-                check_bullet_enemy_collision,
-                check_bullet_player_collision,
-                check_enemy_reached_bottom,
-                check_all_enemies_destroyed,
-                // UI systems
-                update_score_display,
-            )
-                .run_if(in_state(GamePhase::Playing)),
-        )
-        .add_systems(OnExit(GamePhase::Playing), cleanup_game_entities)
-        // Game over systems
-        .add_systems(OnEnter(GamePhase::GameOver), setup_game_over_screen)
-        .add_systems(
-            Update,
-            game_over_screen_input.run_if(in_state(GamePhase::GameOver)),
-        )
-        .add_systems(OnExit(GamePhase::GameOver), cleanup_game_over_screen)
+        .insert_resource(Storage::platform())
+        .add_plugins((
+            GameAssetsPlugin,
+            GameplayPlugin,
+            InterfacePlugin,
+            PersistencePlugin,
+        ))
         .run();
 }
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::components::{EnemyKind, MenuAction};
+
+/// Two hitboxes overlapped this frame: the mask of `entity` matched the layer of `other`
+#[derive(Message)]
+pub struct Collision {
+    pub entity: Entity,
+    pub other: Entity,
+}
+
+/// An enemy was destroyed by a player shot
+#[derive(Message)]
+pub struct EnemyKilled {
+    pub position: Vec3,
+    pub points: u32,
+    pub kind: EnemyKind,
+    /// The enemy was a special spawn (saucer)
+    pub saucer: bool,
+}
+
+/// The player was hit by an enemy shot
+#[derive(Message)]
+pub struct PlayerHit {
+    pub position: Vec3,
+}
+
+/// Who fired a shot
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Shooter {
+    Player,
+    Enemy,
+}
+
+/// A bullet was fired
+#[derive(Message)]
+pub struct ShotFired {
+    pub shooter: Shooter,
+}
+
+/// Every enemy of the current wave was destroyed
+#[derive(Message)]
+pub struct WaveCleared;
+
+/// How a run ended
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RunOutcome {
+    /// The last wave of the campaign was cleared
+    Victory,
+    /// The player was hit by an enemy shot
+    Shot,
+    /// An enemy reached the bottom of the screen
+    Invaded,
+}
+
+/// An achievement was unlocked for the first time
+#[derive(Message)]
+pub struct AchievementUnlocked {
+    pub id: String,
+}
+
+/// The current run is over
+#[derive(Message)]
+pub struct RunEnded {
+    pub outcome: RunOutcome,
+}
+
+/// A menu item was confirmed (`step` 0) or changed with Left/Right (`step` -1
+/// or 1). Back on any menu is sent as `MenuAction::Back`.
+#[derive(Message)]
+pub struct MenuActivated {
+    pub action: MenuAction,
+    pub step: i32,
+}
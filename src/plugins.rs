@@ -0,0 +1,407 @@
+//! The game's schedule, split by concern. `main.rs` adds every plugin on top of
+//! Bevy's `DefaultPlugins`; integration tests add the same plugins under
+//! `MinimalPlugins` and stand in for the engine's rendering, audio, input and
+//! asset plugins, so both run the same systems in the same order.
+
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+use bevy::ui::UiSystems;
+
+use crate::achievements::*;
+use crate::config::*;
+use crate::constants::GAMEPLAY_TICK_RATE;
+use crate::daily::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::Settings;
+use crate::snapshot::SuspendedRun;
+use crate::storage::Storage;
+use crate::systems::*;
+use crate::waves::*;
+
+/// Registers the asset types and their loaders, loads every asset at startup
+/// and keeps text in the current language's font. Needs Bevy's `AssetPlugin`;
+/// tests leave it out and insert empty handles instead.
+pub struct GameAssetsPlugin;
+
+impl Plugin for GameAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .init_asset::<Campaign>()
+            .init_asset_loader::<CampaignLoader>()
+            .init_asset::<AchievementList>()
+            .init_asset_loader::<AchievementListLoader>()
+            .init_asset::<Locale>()
+            .init_asset_loader::<LocaleLoader>()
+            .init_resource::<LocaleFont>()
+            .add_systems(Startup, setup.after(load_settings))
+            // Text is drawn in the font of the current language
+            .add_systems(
+                PostUpdate,
+                (
+                    load_locale_fonts.run_if(resource_changed::<Localization>),
+                    select_locale_font,
+                    apply_locale_font,
+                )
+                    .chain()
+                    .before(UiSystems::Prepare),
+            );
+    }
+}
+
+/// A run from start to finish: player input, enemies, collisions, waves,
+/// scoring, statistics, achievements, the daily challenge, pausing and
+/// continuing suspended runs. Gameplay advances in fixed ticks of
+/// `FixedUpdate`, read from the input of each tick, so that a run played again
+/// with the same seed and input log ends with the same score.
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GamePhase>()
+            .add_sub_state::<RunPhase>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameState>()
+            .init_resource::<Difficulty>()
+            .init_resource::<RunStats>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<Combo>()
+            .init_resource::<ActiveEffects>()
+            .init_resource::<GamepadAssignment>()
+            .init_resource::<PointerInput>()
+            .init_resource::<TickInput>()
+            .init_resource::<PendingFire>()
+            .insert_resource(Time::<Fixed>::from_hz(GAMEPLAY_TICK_RATE))
+            .init_resource::<GameRng>()
+            .init_resource::<RunMode>()
+            .init_resource::<InputLog>()
+            .init_resource::<AchievementList>()
+            .init_resource::<AchievementProgress>()
+            .init_resource::<GameOverTimer>()
+            .init_resource::<CollisionGrid>()
+            .init_resource::<CurrentWave>()
+            .init_resource::<EntityPools>()
+            .add_message::<Collision>()
+            .add_message::<EnemyKilled>()
+            .add_message::<PlayerHit>()
+            .add_message::<ShotFired>()
+            .add_message::<WaveCleared>()
+            .add_message::<RunEnded>()
+            .add_message::<AchievementUnlocked>()
+            .add_systems(Update, (apply_game_config, apply_achievement_list))
+            .add_systems(
+                PreUpdate,
+                (
+                    assign_gamepads.after(InputSystems),
+                    read_pointer
+                        .after(InputSystems)
+                        .after(UiSystems::Focus)
+                        .after(update_window_dimensions),
+                    latch_fire_press
+                        .after(assign_gamepads)
+                        .after(read_pointer)
+                        .run_if(in_state(RunPhase::Running)),
+                ),
+            )
+            // Pools are also kept after every tick, so that a shot released in
+            // one tick can be fired again in the next however many ticks a
+            // frame runs
+            .add_systems(PostUpdate, maintain_entity_pools)
+            .add_systems(FixedPostUpdate, maintain_entity_pools)
+            .add_systems(
+                OnEnter(GamePhase::Playing),
+                (
+                    spawn_player,
+                    (
+                        spawn_enemies,
+                        reset_run_stats,
+                        reset_game_state,
+                        reset_achievement_progress,
+                        seed_run,
+                        discard_suspended_run,
+                    )
+                        .run_if(not(resuming_run)),
+                    restore_run.run_if(resuming_run),
+                    reset_combo,
+                    reset_active_effects,
+                    reset_tick_input,
+                )
+                    .chain(),
+            )
+            // Input of the tick: the player's controls, or the input log being
+            // replayed
+            .add_systems(
+                FixedPreUpdate,
+                (
+                    read_tick_input.run_if(not(resource_exists::<InputReplay>)),
+                    replay_tick_input.run_if(resource_exists::<InputReplay>),
+                )
+                    .run_if(in_state(RunPhase::Running).and(run_in_progress)),
+            )
+            .add_systems(
+                Update,
+                (
+                    // Background systems
+                    spawn_stars,
+                    update_stars,
+                    // Explosion system
+                    update_explosions,
+                )
+                    .run_if(in_state(RunPhase::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    // Entity lifetimes, which stand still while paused
+                    expire_lifetimes,
+                    despawn_offscreen,
+                    // Player systems
+                    player_movement,
+                    player_shoot,
+                    // Physics for every moving entity
+                    integrate_motion
+                        .after(move_enemies)
+                        .after(player_shoot)
+                        .after(enemy_shoot)
+                        .before(rebuild_collision_grid),
+                    // Enemy systems
+                    move_enemies,
+                    enemy_shoot,
+                    spawn_special_enemies,
+                    // Collision systems
+                    (
+                        rebuild_collision_grid,
+                        detect_collisions,
+                        (check_bullet_enemy_collision, check_bullet_player_collision),
+                    )
+                        .chain(),
+                    check_enemy_reached_bottom,
+                    (check_all_enemies_destroyed, advance_wave).chain(),
+                    update_combo.after(award_kill_points).after(advance_wave),
+                    tick_active_effects,
+                    track_time_survived,
+                    track_player_retreat.after(player_movement),
+                    record_daily_input.run_if(daily_run),
+                )
+                    .run_if(in_state(RunPhase::Running).and(run_in_progress)),
+            )
+            .add_systems(Update, toggle_pause.run_if(in_state(GamePhase::Playing)))
+            // Sound and visual feedback, for the ticks of the frame
+            .add_systems(
+                Update,
+                (
+                    play_gameplay_sounds,
+                    spawn_gameplay_explosions,
+                    (add_screen_shake, shake_camera).chain(),
+                ),
+            )
+            // Scoring and records, run after the producers so that messages
+            // written in the tick the game ends are still handled
+            .add_systems(
+                FixedUpdate,
+                (
+                    award_kill_points,
+                    collect_run_stats,
+                    record_lifetime_stats,
+                    evaluate_achievements.run_if(in_state(GamePhase::Playing)),
+                    record_daily_result.run_if(in_state(GamePhase::Playing)),
+                )
+                    .chain()
+                    .after(player_shoot)
+                    .after(enemy_shoot)
+                    .after(check_bullet_enemy_collision)
+                    .after(check_bullet_player_collision)
+                    .after(check_enemy_reached_bottom)
+                    .after(advance_wave),
+            )
+            .add_systems(
+                FixedUpdate,
+                finish_run
+                    .after(check_bullet_player_collision)
+                    .after(check_enemy_reached_bottom)
+                    .after(advance_wave)
+                    .run_if(in_state(RunPhase::Running)),
+            )
+            .add_systems(OnExit(GamePhase::Playing), release_pooled_entities)
+            .add_systems(OnExit(GamePhase::GameOver), reset_game_state);
+    }
+}
+
+/// Screens, menus, the HUD, notifications, on-screen buttons, the window and
+/// the UI language
+pub struct InterfacePlugin;
+
+impl Plugin for InterfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WindowDimensions>()
+            .init_resource::<InitialsEntry>()
+            .init_resource::<RebindingMenu>()
+            .init_resource::<Localization>()
+            .init_resource::<LocaleHandles>()
+            .add_message::<MenuActivated>()
+            .add_systems(Update, update_achievement_toasts)
+            .add_systems(
+                Update,
+                (
+                    toggle_fullscreen,
+                    apply_settings.run_if(resource_changed::<Settings>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_language,
+                    refresh_localized_texts.run_if(resource_changed::<Localization>),
+                    refresh_binding_hints
+                        .run_if(resource_changed::<Localization>.or(resource_changed::<Settings>)),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                fit_camera_to_window.run_if(resource_changed::<WindowDimensions>),
+            )
+            .add_systems(PreUpdate, update_window_dimensions)
+            // Menu systems, shared by every screen with a menu
+            .add_systems(Update, (menu_input, update_menu_display).chain())
+            // Start screen systems
+            .add_systems(OnEnter(GamePhase::StartScreen), setup_start_screen)
+            .add_systems(
+                Update,
+                (
+                    discard_incompatible_run
+                        .after(apply_game_config)
+                        .before(start_menu_input),
+                    start_menu_input
+                        .after(menu_input)
+                        .before(update_menu_display),
+                )
+                    .run_if(in_state(GamePhase::StartScreen)),
+            )
+            // Settings screen systems
+            .add_systems(OnEnter(GamePhase::Settings), setup_settings_screen)
+            .add_systems(
+                Update,
+                settings_screen_input
+                    .after(menu_input)
+                    .before(update_menu_display)
+                    .run_if(in_state(GamePhase::Settings)),
+            )
+            // High scores screen systems
+            .add_systems(OnEnter(GamePhase::HighScores), setup_high_scores_screen)
+            .add_systems(
+                Update,
+                high_scores_screen_input
+                    .after(menu_input)
+                    .run_if(in_state(GamePhase::HighScores)),
+            )
+            // Statistics screen systems
+            .add_systems(OnEnter(GamePhase::Stats), setup_stats_screen)
+            .add_systems(
+                Update,
+                stats_screen_input.run_if(in_state(GamePhase::Stats)),
+            )
+            // Achievements gallery systems
+            .add_systems(OnEnter(GamePhase::Achievements), setup_achievements_screen)
+            .add_systems(
+                Update,
+                achievements_screen_input.run_if(in_state(GamePhase::Achievements)),
+            )
+            // Controls screen systems
+            .add_systems(OnEnter(GamePhase::Controls), setup_controls_screen)
+            .add_systems(
+                Update,
+                (controls_screen_input, update_bindings_display)
+                    .chain()
+                    .run_if(in_state(GamePhase::Controls)),
+            )
+            // HUD and touch controls of a run
+            .add_systems(
+                OnEnter(GamePhase::Playing),
+                (
+                    spawn_touch_controls.after(spawn_player),
+                    spawn_hud.after(reset_combo),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_score_display,
+                    update_high_score_display,
+                    update_wave_display,
+                    update_lives_display,
+                    update_status_display,
+                    update_combo_display,
+                    show_touch_controls,
+                )
+                    .run_if(in_state(GamePhase::Playing)),
+            )
+            .add_systems(Update, spawn_achievement_toasts)
+            // Pause menu systems
+            .add_systems(OnEnter(RunPhase::Paused), setup_pause_menu)
+            .add_systems(
+                Update,
+                (pause_menu_input, suspend_run)
+                    .after(menu_input)
+                    .before(update_menu_display)
+                    .run_if(in_state(RunPhase::Paused)),
+            )
+            // Initials entry systems
+            .add_systems(OnEnter(GamePhase::NameEntry), setup_name_entry_screen)
+            .add_systems(
+                Update,
+                (name_entry_input, update_initials_display)
+                    .chain()
+                    .run_if(in_state(GamePhase::NameEntry)),
+            )
+            // Game over systems
+            .add_systems(OnEnter(GamePhase::GameOver), setup_game_over_screen)
+            .add_systems(
+                Update,
+                game_over_screen_input
+                    .after(menu_input)
+                    .run_if(in_state(GamePhase::GameOver)),
+            );
+    }
+}
+
+/// Loading and saving data kept between sessions. Uses the `Storage` resource
+/// if one was inserted before the plugin is added, disabled storage otherwise.
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Storage>()
+            .init_resource::<HighScores>()
+            .init_resource::<LifetimeStats>()
+            .init_resource::<Settings>()
+            .init_resource::<SuspendedRun>()
+            .init_resource::<DailyScores>()
+            .init_resource::<UnlockedAchievements>()
+            .add_systems(
+                Startup,
+                (
+                    load_high_scores,
+                    load_daily_scores,
+                    load_lifetime_stats,
+                    load_suspended_run,
+                    load_achievements,
+                    load_settings,
+                ),
+            )
+            .add_systems(
+                Update,
+                save_settings
+                    .after(apply_settings)
+                    .run_if(resource_changed::<Settings>),
+            )
+            .add_systems(
+                Last,
+                suspend_run_on_exit.run_if(in_state(GamePhase::Playing)),
+            )
+            .add_systems(OnExit(GamePhase::NameEntry), save_high_scores);
+    }
+}
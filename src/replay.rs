@@ -0,0 +1,108 @@
+//! Playing a daily challenge again from its seed and input log, without a
+//! window, to check the score of its code.
+
+use std::time::Duration;
+
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::CursorMoved;
+
+use crate::achievements::AchievementList;
+use crate::config::GameConfig;
+use crate::constants::GAMEPLAY_TICK_RATE;
+use crate::daily::{DailyCode, DailyCodeError, InputLog, InputReplay};
+use crate::plugins::{GameplayPlugin, PersistencePlugin};
+use crate::resources::*;
+use crate::waves::Campaign;
+
+/// Gameplay ticks run by each frame of a replay
+const TICKS_PER_FRAME: u32 = 8;
+
+/// Play a run from `seed` with the input of `input_log`, on the given config and
+/// campaign, and return its score. `None` if the run does not end on the last
+/// tick of the log.
+pub fn replay(
+    seed: u64,
+    input_log: &InputLog,
+    config: &GameConfig,
+    campaign: &Campaign,
+) -> Option<u32> {
+    let mut app = replay_app(seed, input_log, config, campaign);
+    app.world_mut()
+        .resource_mut::<NextState<GamePhase>>()
+        .set(GamePhase::Playing);
+
+    // One extra frame for the run to leave the playing phase once it has ended
+    let frames = input_log.ticks().div_ceil(TICKS_PER_FRAME) + 2;
+    for _ in 0..frames {
+        app.update();
+        if *app.world().resource::<State<GamePhase>>().get() != GamePhase::Playing {
+            break;
+        }
+    }
+
+    let world = app.world();
+    let ended = *world.resource::<State<GamePhase>>().get() != GamePhase::Playing;
+    (ended && world.resource::<InputReplay>().finished())
+        .then(|| world.resource::<GameState>().score)
+}
+
+/// Check a daily challenge code against the input log of its run, by replaying
+/// the run on the given config and campaign
+pub fn verify(
+    code: &DailyCode,
+    input_log: &InputLog,
+    config: &GameConfig,
+    campaign: &Campaign,
+) -> Result<(), DailyCodeError> {
+    if input_log.hash() != code.input_hash {
+        return Err(DailyCodeError::InputLog);
+    }
+    match replay(code.seed, input_log, config, campaign) {
+        None => Err(DailyCodeError::Unfinished),
+        Some(score) if score != code.score => Err(DailyCodeError::Score(score)),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Headless app running the gameplay systems, with the input log standing in
+/// for the player and no storage
+fn replay_app(seed: u64, input_log: &InputLog, config: &GameConfig, campaign: &Campaign) -> App {
+    let frame = Duration::from_secs_f64(f64::from(TICKS_PER_FRAME) / GAMEPLAY_TICK_RATE);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(frame))
+        // Input and window, never touched
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<Touches>()
+        .init_resource::<WindowDimensions>()
+        .add_message::<CursorMoved>()
+        // Content of the run
+        .init_resource::<GameTextures>()
+        .init_resource::<GameAudio>()
+        .init_resource::<Assets<GameConfig>>()
+        .init_resource::<Assets<Campaign>>()
+        .init_resource::<Assets<AchievementList>>()
+        .add_message::<AssetEvent<GameConfig>>()
+        .add_message::<AssetEvent<AchievementList>>()
+        .insert_resource(GameConfigHandle(Handle::default()))
+        .insert_resource(AchievementListHandle(Handle::default()))
+        .insert_resource(config.clone())
+        .insert_resource(Difficulty::Normal)
+        .insert_resource(InputReplay::new(seed, input_log))
+        .add_plugins((GameplayPlugin, PersistencePlugin));
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Campaign>>()
+        .add(campaign.clone());
+    app.insert_resource(CampaignHandle(handle));
+
+    // Let the pools fill before the run starts
+    app.update();
+    app
+}
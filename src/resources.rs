@@ -1,22 +1,123 @@
+use bevy::ecs::entity::EntityHashSet;
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
+use chrono::NaiveDate;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::AchievementList;
+use crate::components::{EnemyKind, PoolKind};
+use crate::config::{EnemyConfig, GameConfig};
+use crate::constants::{
+    COLLISION_GRID_CELL_SIZE, HIGH_SCORE_TABLE_SIZE, MAX_LOCAL_PLAYERS, PLAYER_LIVES,
+    PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH,
+};
+use crate::input::{Action, TapTarget};
+use crate::locale::Locale;
+use crate::messages::RunOutcome;
+use crate::storage::Persistent;
+use crate::waves::{Campaign, WaveDefinition};
 
 /// Game phase states
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GamePhase {
     #[default]
     StartScreen,
+    /// Lifetime statistics, opened from the start screen
+    Stats,
+    /// Achievements gallery, opened from the start screen
+    Achievements,
+    /// Controls screen for rebinding actions, opened from the start screen
+    Controls,
+    /// Audio, display and input settings, opened from the start screen
+    Settings,
+    /// High score table, opened from the start screen
+    HighScores,
     Playing,
+    /// Initials entry after a run that made the high score table
+    NameEntry,
     GameOver,
 }
 
+/// Whether the run is being played or paused. Only exists in
+/// `GamePhase::Playing`, so pausing keeps the run's entities.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(GamePhase = GamePhase::Playing)]
+pub enum RunPhase {
+    #[default]
+    Running,
+    /// Gameplay is stopped and the pause menu is open
+    Paused,
+}
+
+/// Difficulty the run is played on, recorded with high scores
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Localization key of the difficulty's label
+    pub fn key(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty.easy",
+            Difficulty::Normal => "difficulty.normal",
+            Difficulty::Hard => "difficulty.hard",
+        }
+    }
+
+    /// Multiplier on the wave's enemy speed
+    pub fn enemy_speed_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// Multiplier on the wave's time between enemy shots
+    pub fn fire_interval_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.7,
+        }
+    }
+
+    /// The difficulty `step` places further along `ALL`, wrapping around
+    pub fn cycle(self, step: i32) -> Self {
+        let len = Self::ALL.len() as i32;
+        let index = Self::ALL.iter().position(|d| *d == self).unwrap_or(0) as i32;
+        Self::ALL[(index + step).rem_euclid(len) as usize]
+    }
+}
+
 /// Global game state resource
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub score: u32,
     pub enemy_direction: f32,
     pub enemy_shoot_timer: f32,
+    /// Time since the player's last shot, for autofire. Added after runs could
+    /// be suspended, so older snapshots start at zero.
+    #[serde(default)]
+    pub player_fire_timer: f32,
     pub star_spawn_timer: f32,
     pub victory: bool,
+    /// Ships left, including the ones in play. Added after runs could be
+    /// suspended, so older snapshots resume with the default count.
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+}
+
+fn default_lives() -> u32 {
+    PLAYER_LIVES
 }
 
 impl Default for GameState {
@@ -25,24 +126,66 @@ impl Default for GameState {
             score: 0,
             enemy_direction: 1.0,
             enemy_shoot_timer: 0.0,
+            player_fire_timer: 0.0,
             star_spawn_timer: 0.0,
             victory: false,
+            lives: PLAYER_LIVES,
         }
     }
 }
 
 impl GameState {
-    pub fn reset(&mut self) {
+    /// Start a new run with `lives` ships
+    pub fn reset(&mut self, lives: u32) {
         self.score = 0;
         self.enemy_direction = 1.0;
         self.enemy_shoot_timer = 0.0;
+        self.player_fire_timer = 0.0;
         self.star_spawn_timer = 0.0;
         self.victory = false;
+        self.lives = lives;
+    }
+}
+
+/// Random number generator for gameplay decisions such as which enemy fires.
+/// Its whole state is one number (SplitMix64), so it can be saved with a run.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::seeded(rand::random())
+    }
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        rand::rand_core::impls::fill_bytes_via_next(self, dst);
     }
 }
 
 /// Texture handles resource
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct GameTextures {
     pub player: Handle<Image>,
     pub enemy1: Handle<Image>,
@@ -52,8 +195,18 @@ pub struct GameTextures {
     pub enemy_bullet: Handle<Image>,
 }
 
+impl GameTextures {
+    pub fn enemy(&self, kind: EnemyKind) -> Handle<Image> {
+        match kind {
+            EnemyKind::Type1 => self.enemy1.clone(),
+            EnemyKind::Type2 => self.enemy2.clone(),
+            EnemyKind::Type3 => self.enemy3.clone(),
+        }
+    }
+}
+
 /// Audio handles resource
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct GameAudio {
     pub player_shoot: Handle<AudioSource>,
     pub enemy_shoot: Handle<AudioSource>,
@@ -62,8 +215,192 @@ pub struct GameAudio {
     pub game_music: Handle<AudioSource>,
 }
 
-/// Window dimensions resource
+/// Game configuration asset handle resource
 #[derive(Resource)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
+
+/// Campaign asset handle resource
+#[derive(Resource)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+/// Achievement definitions asset handle resource
+#[derive(Resource)]
+pub struct AchievementListHandle(pub Handle<AchievementList>);
+
+/// Translation asset handles resource, one per entry of `LANGUAGES`
+#[derive(Resource, Default)]
+pub struct LocaleHandles(pub Vec<Handle<Locale>>);
+
+/// Fonts of the current language, tried in order, and the first of them that
+/// has loaded. The default handle is Bevy's built-in font.
+#[derive(Resource, Default)]
+pub struct LocaleFont {
+    pub chain: Vec<Handle<Font>>,
+    pub current: Handle<Font>,
+}
+
+/// Wave currently being played
+#[derive(Resource)]
+pub struct CurrentWave {
+    pub index: usize,
+    pub definition: WaveDefinition,
+    pub elapsed: f32,
+    pub total_enemies: usize,
+    pub next_special_spawn: usize,
+}
+
+impl Default for CurrentWave {
+    fn default() -> Self {
+        Self::new(0, WaveDefinition::from_config(&EnemyConfig::default()))
+    }
+}
+
+impl CurrentWave {
+    pub fn new(index: usize, definition: WaveDefinition) -> Self {
+        Self {
+            index,
+            total_enemies: definition.enemy_count(),
+            definition,
+            elapsed: 0.0,
+            next_special_spawn: 0,
+        }
+    }
+
+    /// Fraction of the wave's enemies destroyed so far, from 0.0 to 1.0
+    pub fn progress(&self, remaining_enemies: usize) -> f32 {
+        if self.total_enemies == 0 {
+            return 1.0;
+        }
+        let pending_spawns = self.definition.special_spawns.len() - self.next_special_spawn;
+        1.0 - (remaining_enemies + pending_spawns) as f32 / self.total_enemies as f32
+    }
+
+    /// Whether every special spawn of the wave has appeared
+    pub fn all_spawned(&self) -> bool {
+        self.next_special_spawn >= self.definition.special_spawns.len()
+    }
+}
+
+/// Gamepad used by each local player, in order of connection. Player 1 is the
+/// first slot; the other slots are kept for local multiplayer.
+#[derive(Resource, Default)]
+pub struct GamepadAssignment {
+    pub players: [Option<Entity>; MAX_LOCAL_PLAYERS],
+}
+
+impl GamepadAssignment {
+    /// Index of the player using `gamepad`
+    pub fn slot_of(&self, gamepad: Entity) -> Option<usize> {
+        self.players
+            .iter()
+            .position(|player| *player == Some(gamepad))
+    }
+}
+
+/// How the mouse and touch screen are being used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PointerMode {
+    /// Keyboard or gamepad only
+    #[default]
+    Off,
+    /// The ship follows the cursor and a click fires
+    Mouse,
+    /// On-screen buttons are shown and the ship follows a drag; stays on once
+    /// the first touch is seen
+    Touch,
+}
+
+/// Mouse and touch input of this frame, gathered by `read_pointer`
+#[derive(Resource, Default)]
+pub struct PointerInput {
+    pub mode: PointerMode,
+    /// Playfield x the ship steers towards, if the pointer is driving it
+    pub target_x: Option<f32>,
+    /// On-screen buttons held down, and a held mouse button as Fire
+    pub held: Vec<TapTarget>,
+    /// Entries of `held` that were pressed this frame
+    pub tapped: Vec<TapTarget>,
+}
+
+impl PointerInput {
+    pub fn is_held(&self, target: TapTarget) -> bool {
+        self.held.contains(&target)
+    }
+
+    pub fn just_tapped(&self, target: TapTarget) -> bool {
+        self.tapped.contains(&target)
+    }
+}
+
+/// Player input of one gameplay tick. Gameplay systems read input only from
+/// here, so that a run replays tick for tick from its logged input.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickInput {
+    /// Steering from -32767 (full left) to 32767 (full right)
+    pub steering: i16,
+    /// Fire was pressed since the previous tick
+    pub fire: bool,
+    /// Fire is held down with autofire on
+    pub autofire: bool,
+}
+
+impl TickInput {
+    /// Quantize steering from -1.0 to 1.0
+    pub fn quantize_steering(steering: f32) -> i16 {
+        (steering.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16
+    }
+
+    /// Steering from -1.0 (left) to 1.0 (right)
+    pub fn steering(&self) -> f32 {
+        f32::from(self.steering) / f32::from(i16::MAX)
+    }
+}
+
+/// Fire presses seen since the last gameplay tick. A frame can run no tick or
+/// several, so presses are held here until a tick takes them.
+#[derive(Resource, Default)]
+pub struct PendingFire(pub bool);
+
+/// Chain of kills, each within `COMBO_WINDOW` seconds of the one before
+#[derive(Resource, Default)]
+pub struct Combo {
+    pub count: u32,
+    /// Seconds left to extend the chain
+    pub remaining: f32,
+}
+
+/// Timed effect on the current run, shown in the HUD status list
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveEffect {
+    /// Localization key of the effect's name
+    pub name: String,
+    /// Seconds until the effect wears off
+    pub remaining: f32,
+}
+
+/// Effects active in the current run, such as power-ups. Nothing grants one
+/// yet, so the list stays empty in play.
+#[derive(Resource, Default)]
+pub struct ActiveEffects {
+    pub effects: Vec<ActiveEffect>,
+}
+
+/// Camera shake built up by hits, decaying over time
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    /// Current shake amount from 0.0 to 1.0; the offset grows with its square
+    pub trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+/// Size of the primary window in logical pixels, kept up to date as it is
+/// resized. The playfield keeps its size and is scaled to fit the window.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
 pub struct WindowDimensions {
     pub width: f32,
     pub height: f32,
@@ -72,49 +409,136 @@ pub struct WindowDimensions {
 impl Default for WindowDimensions {
     fn default() -> Self {
         Self {
-            width: 800.0,
-            height: 600.0,
+            width: WINDOW_WIDTH,
+            height: WINDOW_HEIGHT,
         }
     }
 }
 
-/// High score entry
-#[derive(Clone)]
+impl WindowDimensions {
+    /// Size of a playfield unit on screen, in logical pixels
+    pub fn playfield_scale(&self) -> f32 {
+        (self.width / PLAYFIELD_WIDTH).min(self.height / PLAYFIELD_HEIGHT)
+    }
+
+    /// Playfield position under a window position, with the playfield centered
+    /// in the window
+    pub fn to_playfield(&self, position: Vec2) -> Vec2 {
+        Vec2::new(
+            position.x - self.width / 2.0,
+            self.height / 2.0 - position.y,
+        ) / self.playfield_scale()
+    }
+}
+
+/// High score entry. Fields other than `score` were added after the first save
+/// format and default when missing, so older saves still load.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ScoreEntry {
     pub score: u32,
+    #[serde(default = "unknown_name")]
+    pub name: String,
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+    /// Wave reached, counted from 1
+    #[serde(default)]
+    pub wave: usize,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Set for the score of the run that just ended; never saved
+    #[serde(skip)]
     pub is_current: bool,
 }
 
-/// High scores resource
-#[derive(Resource)]
+fn unknown_name() -> String {
+    "---".to_string()
+}
+
+impl ScoreEntry {
+    /// An entry with only a score, as recorded before initials were entered
+    pub fn new(score: u32) -> Self {
+        Self {
+            score,
+            name: unknown_name(),
+            date: None,
+            wave: 0,
+            difficulty: Difficulty::default(),
+            is_current: false,
+        }
+    }
+}
+
+/// High scores resource, saved between sessions
+#[derive(Resource, Serialize, Deserialize)]
 pub struct HighScores {
     pub scores: Vec<ScoreEntry>,
+    /// Number of scores kept in the table, from `GameConfig::high_scores`
+    #[serde(skip, default = "default_table_size")]
+    pub capacity: usize,
+}
+
+fn default_table_size() -> usize {
+    HIGH_SCORE_TABLE_SIZE
 }
 
 impl Default for HighScores {
     fn default() -> Self {
-        Self { scores: Vec::new() }
+        Self {
+            scores: Vec::new(),
+            capacity: HIGH_SCORE_TABLE_SIZE,
+        }
     }
 }
 
+impl Persistent for HighScores {
+    const KEY: &'static str = "high_scores";
+    const VERSION: u32 = 1;
+}
+
 impl HighScores {
     pub fn add_score(&mut self, score: u32) {
-        // Clear current flags
-        for entry in &mut self.scores {
-            entry.is_current = false;
-        }
+        self.add(ScoreEntry::new(score));
+    }
+
+    /// Add the entry of the run that just ended, marking it as current
+    pub fn add(&mut self, entry: ScoreEntry) {
+        self.clear_current();
 
         // Add new score
         self.scores.push(ScoreEntry {
-            score,
             is_current: true,
+            ..entry
         });
 
         // Sort by score descending
-        self.scores.sort_by(|a, b| b.score.cmp(&a.score));
+        self.scores
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+
+        // Keep only the top of the table
+        self.scores.truncate(self.capacity);
+    }
+
+    /// Whether `score` would make it into the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.scores.len() < self.capacity
+                || self
+                    .scores
+                    .last()
+                    .is_some_and(|lowest| score > lowest.score))
+    }
 
-        // Keep only top 5
-        self.scores.truncate(5);
+    /// Stop highlighting the previous run's entry
+    pub fn clear_current(&mut self) {
+        for entry in &mut self.scores {
+            entry.is_current = false;
+        }
+    }
+
+    /// Change the table size, dropping scores that no longer fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.scores.truncate(capacity);
     }
 
     pub fn get_top_scores(&self) -> &[ScoreEntry] {
@@ -122,6 +546,200 @@ impl HighScores {
     }
 }
 
+/// Enemies destroyed, per enemy type
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KillCounts {
+    pub type1: u32,
+    pub type2: u32,
+    pub type3: u32,
+}
+
+impl KillCounts {
+    pub fn add(&mut self, kind: EnemyKind) {
+        match kind {
+            EnemyKind::Type1 => self.type1 += 1,
+            EnemyKind::Type2 => self.type2 += 1,
+            EnemyKind::Type3 => self.type3 += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.type1 + self.type2 + self.type3
+    }
+}
+
+/// Runs ended, per way of ending
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutcomeCounts {
+    pub victories: u32,
+    pub shot: u32,
+    pub invaded: u32,
+}
+
+impl OutcomeCounts {
+    pub fn add(&mut self, outcome: RunOutcome) {
+        match outcome {
+            RunOutcome::Victory => self.victories += 1,
+            RunOutcome::Shot => self.shot += 1,
+            RunOutcome::Invaded => self.invaded += 1,
+        }
+    }
+}
+
+/// Gameplay statistics, for one run or summed over many
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub shots_fired: u32,
+    /// Player shots that destroyed an enemy
+    pub hits: u32,
+    pub kills: KillCounts,
+    /// Seconds spent playing
+    pub time_survived: f32,
+    pub waves_cleared: u32,
+    pub saucers_hit: u32,
+    pub outcomes: OutcomeCounts,
+}
+
+impl Stats {
+    /// Fraction of shots that hit, from 0.0 to 1.0
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+
+    /// Add another set of statistics to this one
+    pub fn accumulate(&mut self, other: &Stats) {
+        self.shots_fired += other.shots_fired;
+        self.hits += other.hits;
+        self.kills.type1 += other.kills.type1;
+        self.kills.type2 += other.kills.type2;
+        self.kills.type3 += other.kills.type3;
+        self.time_survived += other.time_survived;
+        self.waves_cleared += other.waves_cleared;
+        self.saucers_hit += other.saucers_hit;
+        self.outcomes.victories += other.outcomes.victories;
+        self.outcomes.shot += other.outcomes.shot;
+        self.outcomes.invaded += other.outcomes.invaded;
+    }
+}
+
+/// Statistics of the current or last run
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub stats: Stats,
+    /// How the run ended, once it has
+    pub outcome: Option<RunOutcome>,
+}
+
+/// Statistics summed over every run, saved between sessions
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    pub best_score: u32,
+    pub totals: Stats,
+}
+
+impl Persistent for LifetimeStats {
+    const KEY: &'static str = "stats";
+    const VERSION: u32 = 1;
+}
+
+impl LifetimeStats {
+    /// Add a finished run
+    pub fn record(&mut self, run: &Stats, score: u32) {
+        self.runs += 1;
+        self.best_score = self.best_score.max(score);
+        self.totals.accumulate(run);
+    }
+}
+
+/// Initials being entered for a new high score
+#[derive(Resource)]
+pub struct InitialsEntry {
+    pub letters: [char; 3],
+    /// Index of the letter being edited
+    pub cursor: usize,
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        Self {
+            letters: ['A'; 3],
+            cursor: 0,
+        }
+    }
+}
+
+impl InitialsEntry {
+    /// Step the letter under the cursor through A-Z, wrapping around
+    pub fn cycle(&mut self, step: i32) {
+        let letter = &mut self.letters[self.cursor];
+        let index = (*letter as i32 - 'A' as i32 + step).rem_euclid(26);
+        *letter = char::from(b'A' + index as u8);
+    }
+
+    /// Move the cursor, staying on the first or last letter at the ends
+    pub fn move_cursor(&mut self, step: i32) {
+        let last = self.letters.len() as i32 - 1;
+        self.cursor = (self.cursor as i32 + step).clamp(0, last) as usize;
+    }
+
+    /// Set the letter under the cursor and move to the next one
+    pub fn type_letter(&mut self, letter: char) {
+        self.letters[self.cursor] = letter.to_ascii_uppercase();
+        self.move_cursor(1);
+    }
+
+    pub fn name(&self) -> String {
+        self.letters.iter().collect()
+    }
+}
+
+/// A row of the controls screen: every action, then the autofire setting and
+/// resetting the bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsRow {
+    Action(Action),
+    Autofire,
+    Reset,
+}
+
+/// State of the controls screen
+#[derive(Resource, Default)]
+pub struct RebindingMenu {
+    /// Index of the selected row, see `selected_row`
+    pub selected: usize,
+    /// Waiting for an input to bind to the selected action
+    pub capturing: bool,
+    /// Result of the last change, shown below the list
+    pub status: String,
+}
+
+impl RebindingMenu {
+    const ROWS: usize = Action::ALL.len() + 2;
+
+    pub fn selected_row(&self) -> ControlsRow {
+        match Action::ALL.get(self.selected) {
+            Some(action) => ControlsRow::Action(*action),
+            None if self.selected == Action::ALL.len() => ControlsRow::Autofire,
+            None => ControlsRow::Reset,
+        }
+    }
+
+    /// Move the selection, wrapping around at either end of the list
+    pub fn move_selection(&mut self, step: i32) {
+        let count = Self::ROWS as i32;
+        self.selected = (self.selected as i32 + step).rem_euclid(count) as usize;
+    }
+}
+
 /// Game over timer resource for preventing immediate restart
 #[derive(Resource)]
 pub struct GameOverTimer {
@@ -147,3 +765,131 @@ impl GameOverTimer {
         self.elapsed >= self.required_delay
     }
 }
+
+/// Uniform grid broadphase shared by the collision systems, rebuilt every frame
+#[derive(Resource)]
+pub struct CollisionGrid {
+    pub cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl Default for CollisionGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: COLLISION_GRID_CELL_SIZE,
+            cells: HashMap::default(),
+        }
+    }
+}
+
+impl CollisionGrid {
+    /// Remove all entries while keeping the cell allocations for the next frame
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    /// Insert an entity into every cell overlapped by its bounds
+    pub fn insert(&mut self, entity: Entity, center: Vec2, half_size: Vec2) {
+        let min = self.cell_of(center - half_size);
+        let max = self.cell_of(center + half_size);
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Collect every entity sharing a cell with the given bounds into `out`
+    pub fn query(&self, center: Vec2, half_size: Vec2, out: &mut Vec<Entity>) {
+        out.clear();
+
+        let min = self.cell_of(center - half_size);
+        let max = self.cell_of(center + half_size);
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                if let Some(cell) = self.cells.get(&IVec2::new(x, y)) {
+                    out.extend_from_slice(cell);
+                }
+            }
+        }
+
+        // Entities spanning several cells are reported once
+        out.sort_unstable();
+        out.dedup();
+    }
+
+    fn cell_of(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+}
+
+/// Pre-spawned entities of one type, recycled instead of spawned and despawned.
+/// Inactive entities carry Bevy's `Disabled` component, which hides them from queries.
+#[derive(Default)]
+pub struct EntityPool {
+    free: Vec<Entity>,
+    released: Vec<Entity>,
+    inactive: EntityHashSet,
+    size: usize,
+}
+
+impl EntityPool {
+    /// Total number of entities owned by the pool
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Register a newly spawned, disabled entity with the pool
+    pub fn add(&mut self, entity: Entity) {
+        self.free.push(entity);
+        self.inactive.insert(entity);
+        self.size += 1;
+    }
+
+    /// Take an inactive entity for reuse, or `None` when the pool is exhausted.
+    /// The caller must reactivate it by removing `Disabled`.
+    pub fn acquire(&mut self) -> Option<Entity> {
+        let entity = self.free.pop()?;
+        self.inactive.remove(&entity);
+        Some(entity)
+    }
+
+    /// Disable an entity and return it to the pool. Releasing an entity twice is a no-op.
+    pub fn release(&mut self, commands: &mut Commands, entity: Entity) {
+        if self.inactive.insert(entity) {
+            commands.entity(entity).insert(Disabled);
+            self.released.push(entity);
+        }
+    }
+
+    /// Make entities released this frame available again. Run once their `Disabled`
+    /// commands have been applied, so an entity is never reactivated and disabled in
+    /// the same frame.
+    pub fn recycle_released(&mut self) {
+        self.free.append(&mut self.released);
+    }
+}
+
+/// Entity pools for short-lived, frequently spawned entities
+#[derive(Resource, Default)]
+pub struct EntityPools {
+    pub stars: EntityPool,
+    pub explosion_particles: EntityPool,
+    pub bullets: EntityPool,
+    pub enemy_bullets: EntityPool,
+}
+
+impl EntityPools {
+    pub fn get_mut(&mut self, kind: PoolKind) -> &mut EntityPool {
+        match kind {
+            PoolKind::Star => &mut self.stars,
+            PoolKind::ExplosionParticle => &mut self.explosion_particles,
+            PoolKind::Bullet => &mut self.bullets,
+            PoolKind::EnemyBullet => &mut self.enemy_bullets,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, VideoModeSelection, WindowMode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::constants::*;
+use crate::input::ActionBindings;
+use crate::locale::DEFAULT_LANGUAGE;
+use crate::storage::Persistent;
+
+/// Player settings, saved between sessions and applied live when changed
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Volume applied on top of the music and sound effect volumes, 0.0 to 1.0
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    /// Inputs bound to each action, saved as `key_bindings` before mouse and
+    /// gamepad inputs could be bound
+    #[serde(alias = "key_bindings")]
+    pub bindings: ActionBindings,
+    /// Holding Fire keeps shooting at the configured autofire interval
+    pub autofire: bool,
+    /// Left stick travel ignored around the centre, 0.0 to 1.0
+    pub gamepad_deadzone: f32,
+    /// Saved as `fullscreen: true` or `false` before there were several modes
+    #[serde(alias = "fullscreen")]
+    pub display_mode: DisplayMode,
+    /// Strength of the screen shake on hits, 0.0 (off) to 1.0
+    pub screen_shake: f32,
+    /// Multiplier on the star spawn rate; 0.0 hides the starfield
+    pub starfield_density: f32,
+    /// Code of the language of the UI text, one of `LANGUAGES`
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: DEFAULT_MASTER_VOLUME,
+            music_volume: DEFAULT_MUSIC_VOLUME,
+            sfx_volume: DEFAULT_SFX_VOLUME,
+            bindings: ActionBindings::default(),
+            autofire: false,
+            gamepad_deadzone: DEFAULT_GAMEPAD_DEADZONE,
+            display_mode: DisplayMode::Windowed,
+            screen_shake: DEFAULT_SCREEN_SHAKE,
+            starfield_density: DEFAULT_STARFIELD_DENSITY,
+            language: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl Persistent for Settings {
+    const KEY: &'static str = "settings";
+    const VERSION: u32 = 1;
+}
+
+/// How the game window is shown. The playfield keeps its aspect ratio in every
+/// mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    /// Fullscreen window on the current monitor; browser fullscreen on the web
+    Borderless,
+    /// Fullscreen at the monitor's current video mode, native builds only
+    Exclusive,
+}
+
+impl DisplayMode {
+    /// Modes offered on this platform, in menu order
+    #[cfg(not(target_arch = "wasm32"))]
+    pub const AVAILABLE: &[DisplayMode] = &[
+        DisplayMode::Windowed,
+        DisplayMode::Borderless,
+        DisplayMode::Exclusive,
+    ];
+    #[cfg(target_arch = "wasm32")]
+    pub const AVAILABLE: &[DisplayMode] = &[DisplayMode::Windowed, DisplayMode::Borderless];
+
+    /// Name the mode is saved under
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Exclusive => "Exclusive",
+        }
+    }
+
+    /// Localization key of the mode's label
+    pub fn key(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "display_mode.windowed",
+            DisplayMode::Borderless if cfg!(target_arch = "wasm32") => "display_mode.fullscreen",
+            DisplayMode::Borderless => "display_mode.borderless",
+            DisplayMode::Exclusive => "display_mode.exclusive",
+        }
+    }
+
+    /// The mode `step` places further along `AVAILABLE`, wrapping around
+    pub fn cycle(self, step: i32) -> Self {
+        let len = Self::AVAILABLE.len() as i32;
+        let index = Self::AVAILABLE
+            .iter()
+            .position(|mode| *mode == self)
+            .unwrap_or(0) as i32;
+        Self::AVAILABLE[(index + step).rem_euclid(len) as usize]
+    }
+
+    /// Mode after the fullscreen shortcut: windowed goes borderless, either
+    /// fullscreen mode goes back to a window
+    pub fn toggled(self) -> Self {
+        match self {
+            DisplayMode::Windowed => DisplayMode::Borderless,
+            DisplayMode::Borderless | DisplayMode::Exclusive => DisplayMode::Windowed,
+        }
+    }
+
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            // Browsers only offer fullscreen of the page
+            DisplayMode::Exclusive if !cfg!(target_arch = "wasm32") => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+            DisplayMode::Borderless | DisplayMode::Exclusive => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+        }
+    }
+}
+
+impl Serialize for DisplayMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DisplayMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DisplayMode, D::Error> {
+        use serde::de::Error as _;
+
+        /// A mode name, or the fullscreen flag of older saves
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Saved {
+            Fullscreen(bool),
+            Name(String),
+        }
+
+        match Saved::deserialize(deserializer)? {
+            Saved::Fullscreen(true) => Ok(DisplayMode::Borderless),
+            Saved::Fullscreen(false) => Ok(DisplayMode::Windowed),
+            Saved::Name(name) => [
+                DisplayMode::Windowed,
+                DisplayMode::Borderless,
+                DisplayMode::Exclusive,
+            ]
+            .into_iter()
+            .find(|mode| mode.name() == name)
+            .ok_or_else(|| D::Error::custom(format!("unknown display mode {name:?}"))),
+        }
+    }
+}
+
+impl Settings {
+    /// Effective music volume, including the master volume
+    pub fn music_level(&self) -> f32 {
+        (self.master_volume * self.music_volume).clamp(0.0, 1.0)
+    }
+
+    /// Effective sound effect volume, including the master volume
+    pub fn sfx_level(&self) -> f32 {
+        (self.master_volume * self.sfx_volume).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::EnemyKind;
+use crate::daily::{InputLog, RunMode};
+use crate::resources::{Difficulty, GameRng, GameState, Stats};
+use crate::storage::Persistent;
+
+/// A run in progress, captured when the game is suspended so that it can be
+/// continued later exactly where it stopped
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    /// Player position, if the player was alive
+    pub player: Option<[f32; 2]>,
+    pub enemies: Vec<EnemySnapshot>,
+    pub bullets: Vec<BulletSnapshot>,
+    pub enemy_bullets: Vec<BulletSnapshot>,
+    pub game_state: GameState,
+    pub wave: WaveSnapshot,
+    pub stats: Stats,
+    pub difficulty: Difficulty,
+    pub rng: GameRng,
+    /// Added with the daily challenge; older snapshots are standard runs
+    #[serde(default)]
+    pub mode: RunMode,
+    #[serde(default)]
+    pub input_log: InputLog,
+    /// Fingerprint of the config and campaign the run was played with. A run
+    /// whose content has changed since cannot be rebuilt faithfully.
+    pub content: u64,
+}
+
+/// A surviving enemy
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EnemySnapshot {
+    pub kind: EnemyKind,
+    pub position: [f32; 2],
+    /// Formation column the enemy sways around, see `Enemy::original_x`
+    pub original_x: f32,
+    pub velocity: [f32; 2],
+    pub saucer: bool,
+}
+
+/// A bullet in flight
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BulletSnapshot {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+}
+
+/// Progress through the current wave. The wave definition itself is looked up
+/// again from the campaign by index.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WaveSnapshot {
+    pub index: usize,
+    pub elapsed: f32,
+    pub next_special_spawn: usize,
+}
+
+/// The suspended run, if any, saved between sessions
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct SuspendedRun {
+    pub snapshot: Option<RunSnapshot>,
+    /// Set when "Continue" is chosen, until the run has been restored
+    #[serde(skip)]
+    pub resuming: bool,
+}
+
+impl Persistent for SuspendedRun {
+    const KEY: &'static str = "suspended_run";
+    const VERSION: u32 = 3;
+    // Entity and resource layouts change between builds without notice
+    const BUILD: Option<&'static str> = Some(env!("CARGO_PKG_VERSION"));
+}
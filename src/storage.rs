@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Directory name (native) or key prefix (`localStorage`) for all saved data
+pub const STORAGE_NAMESPACE: &str = "vibe-invaders";
+
+/// Data saved between sessions under a fixed key
+pub trait Persistent: Serialize + DeserializeOwned + Default {
+    /// Storage key, used as the file name on native and the `localStorage` key on the web
+    const KEY: &'static str;
+    /// Format version. Saved data with a different version is rejected.
+    const VERSION: u32;
+    /// Build the data is tied to, for data that only the build which wrote it
+    /// can use. Saved data from another build is rejected and discarded.
+    const BUILD: Option<&'static str> = None;
+}
+
+/// Errors raised while reading or writing saved data
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("storage is not available")]
+    Unavailable,
+    #[error("could not access storage: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse saved data: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not serialize data: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("saved data has format version {found}, expected {expected}")]
+    Version { found: u32, expected: u32 },
+    #[error("saved data is from build {found}, expected {expected}")]
+    Build {
+        found: String,
+        expected: &'static str,
+    },
+}
+
+/// Saved data wrapped with its format version and, for build-bound data, the
+/// build that wrote it
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    build: Option<String>,
+    data: T,
+}
+
+/// Only the version and build of an envelope, read before the data so that
+/// incompatible saves are reported as such rather than as parse errors
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+    #[serde(default)]
+    build: Option<String>,
+}
+
+#[derive(Clone, Default)]
+enum Backend {
+    /// Nothing is read or written, as in tests
+    #[default]
+    Disabled,
+    /// One RON file per key in a directory
+    #[cfg(not(target_arch = "wasm32"))]
+    Directory(std::path::PathBuf),
+    /// The browser's `localStorage`
+    #[cfg(target_arch = "wasm32")]
+    LocalStorage,
+}
+
+/// Where persistent data lives: the platform config directory on native builds,
+/// `localStorage` in the browser. The default storage is disabled.
+#[derive(Resource, Clone, Default)]
+pub struct Storage {
+    backend: Backend,
+}
+
+impl Storage {
+    /// Storage for the current platform
+    pub fn platform() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let backend = match dirs::config_dir() {
+            Some(dir) => Backend::Directory(dir.join(STORAGE_NAMESPACE)),
+            None => {
+                warn!("No config directory found, progress will not be saved");
+                Backend::Disabled
+            }
+        };
+        #[cfg(target_arch = "wasm32")]
+        let backend = Backend::LocalStorage;
+
+        Self { backend }
+    }
+
+    /// Storage in a specific directory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn at(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            backend: Backend::Directory(dir.into()),
+        }
+    }
+
+    /// Read the raw text stored under `key`
+    pub fn read_raw(&self, key: &str) -> Result<Option<String>, StorageError> {
+        match &self.backend {
+            Backend::Disabled => Ok(None),
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Directory(dir) => {
+                match std::fs::read_to_string(dir.join(format!("{key}.ron"))) {
+                    Ok(text) => Ok(Some(text)),
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(error) => Err(error.into()),
+                }
+            }
+            #[cfg(target_arch = "wasm32")]
+            Backend::LocalStorage => local_storage()?
+                .get_item(&format!("{STORAGE_NAMESPACE}.{key}"))
+                .map_err(|_| StorageError::Unavailable),
+        }
+    }
+
+    /// Store raw text under `key`, replacing any previous value
+    pub fn write_raw(&self, key: &str, text: &str) -> Result<(), StorageError> {
+        match &self.backend {
+            Backend::Disabled => Ok(()),
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Directory(dir) => {
+                std::fs::create_dir_all(dir)?;
+                // Write then rename, so a crash mid-write never leaves a truncated file
+                let temporary = dir.join(format!("{key}.ron.tmp"));
+                std::fs::write(&temporary, text)?;
+                std::fs::rename(temporary, dir.join(format!("{key}.ron")))?;
+                Ok(())
+            }
+            #[cfg(target_arch = "wasm32")]
+            Backend::LocalStorage => local_storage()?
+                .set_item(&format!("{STORAGE_NAMESPACE}.{key}"), text)
+                .map_err(|_| StorageError::Unavailable),
+        }
+    }
+
+    /// Delete the value stored under `key`, if any
+    pub fn remove(&self, key: &str) -> Result<(), StorageError> {
+        match &self.backend {
+            Backend::Disabled => Ok(()),
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Directory(dir) => match std::fs::remove_file(dir.join(format!("{key}.ron"))) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+                _ => Ok(()),
+            },
+            #[cfg(target_arch = "wasm32")]
+            Backend::LocalStorage => local_storage()?
+                .remove_item(&format!("{STORAGE_NAMESPACE}.{key}"))
+                .map_err(|_| StorageError::Unavailable),
+        }
+    }
+
+    /// Read saved data, or `None` when nothing has been saved yet
+    pub fn read<T: Persistent>(&self) -> Result<Option<T>, StorageError> {
+        let Some(text) = self.read_raw(T::KEY)? else {
+            return Ok(None);
+        };
+
+        let header: Header = ron::from_str(&text)?;
+        if header.version != T::VERSION {
+            return Err(StorageError::Version {
+                found: header.version,
+                expected: T::VERSION,
+            });
+        }
+        if let Some(expected) = T::BUILD
+            && header.build.as_deref() != Some(expected)
+        {
+            return Err(StorageError::Build {
+                found: header.build.unwrap_or_else(|| "unknown".to_string()),
+                expected,
+            });
+        }
+
+        let envelope: Envelope<T> = ron::from_str(&text)?;
+        Ok(Some(envelope.data))
+    }
+
+    /// Save data, replacing any previous save
+    pub fn write<T: Persistent>(&self, data: &T) -> Result<(), StorageError> {
+        let envelope = Envelope {
+            version: T::VERSION,
+            build: T::BUILD.map(str::to_string),
+            data,
+        };
+        let text = ron::ser::to_string_pretty(&envelope, ron::ser::PrettyConfig::default())?;
+        self.write_raw(T::KEY, &text)
+    }
+
+    /// Read saved data, falling back to the default when nothing was saved or the
+    /// save is unreadable. Unreadable saves are kept under `<key>.corrupt`; saves
+    /// from another build are expected after an update and simply dropped.
+    pub fn load<T: Persistent>(&self) -> T {
+        match self.read() {
+            Ok(data) => data.unwrap_or_default(),
+            Err(error @ StorageError::Build { .. }) => {
+                info!("Discarding saved {}: {error}", T::KEY);
+                T::default()
+            }
+            Err(error) => {
+                warn!("Discarding saved {}: {error}", T::KEY);
+                if let Ok(Some(text)) = self.read_raw(T::KEY) {
+                    let _ = self.write_raw(&format!("{}.corrupt", T::KEY), &text);
+                }
+                T::default()
+            }
+        }
+    }
+
+    /// Save data, logging rather than failing when storage is unavailable
+    pub fn save<T: Persistent>(&self, data: &T) {
+        if let Err(error) = self.write(data) {
+            warn!("Could not save {}: {error}", T::KEY);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, StorageError> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or(StorageError::Unavailable)
+}
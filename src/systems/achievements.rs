@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+
+use crate::achievements::*;
+use crate::components::*;
+use crate::constants::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::storage::*;
+use crate::systems::input::*;
+
+/// Copy the achievement definitions asset into the `AchievementList` resource
+/// whenever it finishes loading or changes on disk
+pub fn apply_achievement_list(
+    mut asset_events: MessageReader<AssetEvent<AchievementList>>,
+    lists: Res<Assets<AchievementList>>,
+    handle: Res<AchievementListHandle>,
+    mut list: ResMut<AchievementList>,
+) {
+    for event in asset_events.read() {
+        if (event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = lists.get(&handle.0)
+        {
+            *list = loaded.clone();
+            info!("Applied achievements from {ACHIEVEMENTS_PATH}");
+        }
+    }
+}
+
+/// Start tracking achievement progress for a new run
+pub fn reset_achievement_progress(mut progress: ResMut<AchievementProgress>) {
+    *progress = AchievementProgress::default();
+}
+
+/// Note when the player moves left, for achievements that forbid it
+pub fn track_player_retreat(
+    player_query: Query<&Transform, With<Player>>,
+    mut progress: ResMut<AchievementProgress>,
+) {
+    if let Ok(transform) = player_query.single() {
+        let x = transform.translation.x;
+        if progress.player_x.is_some_and(|previous| x < previous) {
+            progress.moved_left = true;
+        }
+        progress.player_x = Some(x);
+    }
+}
+
+/// Unlock achievements whose conditions were met this frame and save the unlocks
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_achievements(
+    mut shot_fired: MessageReader<ShotFired>,
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut wave_cleared: MessageReader<WaveCleared>,
+    mut run_ended: MessageReader<RunEnded>,
+    mut achievement_unlocked: MessageWriter<AchievementUnlocked>,
+    mut progress: ResMut<AchievementProgress>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    list: Res<AchievementList>,
+    run_stats: Res<RunStats>,
+    game_state: Res<GameState>,
+    current_wave: Res<CurrentWave>,
+    storage: Res<Storage>,
+    time: Res<Time>,
+) {
+    for shot in shot_fired.read() {
+        if shot.shooter == Shooter::Player {
+            progress.wave_shots += 1;
+        }
+    }
+    for _ in enemy_killed.read() {
+        progress.wave_hits += 1;
+        progress.previous_kill = progress.last_kill;
+        progress.last_kill = Some(time.elapsed_secs());
+    }
+    let cleared = wave_cleared.read().count() > 0;
+    let victory = run_ended
+        .read()
+        .any(|ended| ended.outcome == RunOutcome::Victory);
+
+    let met = |condition: AchievementCondition| match condition {
+        AchievementCondition::Kills(kills) => run_stats.stats.kills.total() >= kills,
+        AchievementCondition::SaucersHit(saucers) => run_stats.stats.saucers_hit >= saucers,
+        AchievementCondition::Score(score) => game_state.score >= score,
+        AchievementCondition::ReachWave(wave) => current_wave.index + 1 >= wave,
+        AchievementCondition::PerfectWave => {
+            cleared && progress.wave_shots > 0 && progress.wave_hits >= progress.wave_shots
+        }
+        AchievementCondition::QuickFinish { seconds } => {
+            cleared
+                && matches!(
+                    (progress.previous_kill, progress.last_kill),
+                    (Some(previous), Some(last)) if last - previous < seconds
+                )
+        }
+        AchievementCondition::NoRetreat => cleared && !progress.moved_left,
+        AchievementCondition::Victory => victory,
+    };
+
+    let now = chrono::Local::now().naive_local();
+    let mut any_unlocked = false;
+    for achievement in &list.achievements {
+        if met(achievement.condition) && unlocked.unlock(&achievement.id, now) {
+            any_unlocked = true;
+            achievement_unlocked.write(AchievementUnlocked {
+                id: achievement.id.clone(),
+            });
+        }
+    }
+
+    if any_unlocked {
+        storage.save(&*unlocked);
+    }
+    if cleared {
+        progress.start_wave();
+    }
+}
+
+/// Show a notification for each newly unlocked achievement, stacked below any
+/// still on screen
+pub fn spawn_achievement_toasts(
+    mut commands: Commands,
+    mut achievement_unlocked: MessageReader<AchievementUnlocked>,
+    toast_query: Query<(), With<AchievementToast>>,
+    list: Res<AchievementList>,
+    localization: Res<Localization>,
+) {
+    let shown = toast_query.iter().count();
+    let unlocked = achievement_unlocked
+        .read()
+        .filter_map(|unlocked| list.get(&unlocked.id));
+    for (slot, achievement) in (shown..).zip(unlocked) {
+        let name = achievement.localized_name(&localization);
+        commands.spawn((
+            Text::new(localization.format("achievements.toast", &[("name", &name)])),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.84, 0.0)),
+            TextLayout::new_with_justify(Justify::Right),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0 + slot as f32 * 55.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            AchievementToast::default(),
+        ));
+    }
+}
+
+/// Fade notifications out over the last part of their duration and remove them
+/// once it has passed
+pub fn update_achievement_toasts(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut AchievementToast,
+        &mut TextColor,
+        &mut BackgroundColor,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut toast, mut text_color, mut background) in query.iter_mut() {
+        toast.elapsed += time.delta_secs();
+        if toast.elapsed >= ACHIEVEMENT_TOAST_DURATION {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let fraction = toast.elapsed / ACHIEVEMENT_TOAST_DURATION;
+        let alpha = ((1.0 - fraction) * 4.0).min(1.0);
+        text_color.0.set_alpha(alpha);
+        background.0.set_alpha(alpha * 0.7);
+    }
+}
+
+/// Setup the achievements gallery
+pub fn setup_achievements_screen(
+    mut commands: Commands,
+    list: Res<AchievementList>,
+    unlocked: Res<UnlockedAchievements>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+) {
+    let unlocked_count = list
+        .achievements
+        .iter()
+        .filter(|achievement| unlocked.get(&achievement.id).is_some())
+        .count();
+
+    // Title text
+    commands.spawn((
+        Text::new(localization.format(
+            "achievements.title",
+            &[
+                ("unlocked", &unlocked_count),
+                ("total", &list.achievements.len()),
+            ],
+        )),
+        TextFont {
+            font_size: 44.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.84, 0.0)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Achievements),
+    ));
+
+    // One entry per achievement: name and description, then when it was unlocked
+    for (i, achievement) in list.achievements.iter().enumerate() {
+        let unlock = unlocked.get(&achievement.id);
+        let (status, color) = match unlock {
+            Some(unlock) => (
+                localization.format(
+                    "achievements.unlocked",
+                    &[("time", &unlock.at.format("%Y-%m-%d %H:%M"))],
+                ),
+                Color::WHITE,
+            ),
+            None => (
+                localization.text("achievements.locked"),
+                Color::srgb(0.45, 0.45, 0.45),
+            ),
+        };
+
+        commands.spawn((
+            Text::new(localization.format(
+                "achievements.entry",
+                &[
+                    ("name", &achievement.localized_name(&localization)),
+                    (
+                        "description",
+                        &achievement.localized_description(&localization),
+                    ),
+                    ("status", &status),
+                ],
+            )),
+            TextFont {
+                font_size: 17.0,
+                ..default()
+            },
+            TextColor(color),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(100.0 + i as f32 * 50.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            DespawnOnExit(GamePhase::Achievements),
+        ));
+    }
+
+    // Return text
+    commands.spawn((
+        Text::new(settings.bindings.hint("common.return", &localization)),
+        BindingHint("common.return"),
+        TextFont {
+            font_size: 25.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(540.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        Button,
+        TapButton(TapTarget::Action(Action::Back)),
+        DespawnOnExit(GamePhase::Achievements),
+    ));
+}
+
+/// Handle achievements gallery input
+pub fn achievements_screen_input(
+    controls: PlayerControls,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    if controls.just_pressed(Action::Back) {
+        next_state.set(GamePhase::StartScreen);
+    }
+}
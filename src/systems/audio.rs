@@ -0,0 +1,34 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+
+/// Play sound effects for gameplay messages
+pub fn play_gameplay_sounds(
+    mut commands: Commands,
+    mut shot_fired: MessageReader<ShotFired>,
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut player_hit: MessageReader<PlayerHit>,
+    audio: Res<GameAudio>,
+    settings: Res<Settings>,
+) {
+    let playback = PlaybackSettings::DESPAWN.with_volume(Volume::Linear(settings.sfx_level()));
+
+    for shot in shot_fired.read() {
+        let sound = match shot.shooter {
+            Shooter::Player => audio.player_shoot.clone(),
+            Shooter::Enemy => audio.enemy_shoot.clone(),
+        };
+        commands.spawn((AudioPlayer::new(sound), playback));
+    }
+
+    for _ in enemy_killed.read() {
+        commands.spawn((AudioPlayer::new(audio.enemy_destroyed.clone()), playback));
+    }
+
+    for _ in player_hit.read() {
+        commands.spawn((AudioPlayer::new(audio.player_destroyed.clone()), playback));
+    }
+}
@@ -1,15 +1,31 @@
+use bevy::ecs::entity_disabling::Disabled;
 use bevy::prelude::*;
 use rand::Rng;
 
 use crate::components::*;
+use crate::config::*;
 use crate::constants::*;
 use crate::resources::*;
+use crate::settings::*;
 
 /// Spawn stars from the center of the screen
-pub fn spawn_stars(mut commands: Commands, mut game_state: ResMut<GameState>, time: Res<Time>) {
+pub fn spawn_stars(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    mut pools: ResMut<EntityPools>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+) {
+    if settings.starfield_density <= 0.0 {
+        return;
+    }
+
+    let starfield = &config.starfield;
     game_state.star_spawn_timer += time.delta_secs();
 
-    if game_state.star_spawn_timer >= STAR_SPAWN_INTERVAL {
+    // A denser starfield spawns stars more often
+    if game_state.star_spawn_timer >= starfield.spawn_interval / settings.starfield_density {
         game_state.star_spawn_timer = 0.0;
 
         let mut rng = rand::rng();
@@ -18,47 +34,50 @@ pub fn spawn_stars(mut commands: Commands, mut game_state: ResMut<GameState>, ti
         let angle = rng.random_range(0.0..std::f32::consts::TAU);
 
         // Random speed variation
-        let speed = STAR_BASE_SPEED * rng.random_range(0.5..1.5);
+        let speed = starfield.base_speed * rng.random_range(0.5..1.5);
 
         // Spawn star at center with slight random offset
         let offset = rng.random_range(-10.0..10.0);
         let x = offset * angle.cos();
         let y = offset * angle.sin();
 
-        commands.spawn((
-            Sprite {
-                color: Color::srgb(1.0, 1.0, 1.0),
-                custom_size: Some(Vec2::new(STAR_MIN_SIZE, STAR_MIN_SIZE)),
-                ..default()
-            },
-            Transform::from_xyz(x, y, -1.0), // Behind everything
-            Star {
-                distance_from_center: 0.0,
-            },
-            Velocity {
-                x: speed * angle.cos(),
-                y: speed * angle.sin(),
-            },
-        ));
+        // Skip this star if every pooled star is on screen
+        let Some(star) = pools.stars.acquire() else {
+            return;
+        };
+
+        commands
+            .entity(star)
+            .insert((
+                Sprite {
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    custom_size: Some(Vec2::new(starfield.min_size, starfield.min_size)),
+                    ..default()
+                },
+                Transform::from_xyz(x, y, -1.0), // Behind everything
+                Star {
+                    distance_from_center: 0.0,
+                },
+                Velocity {
+                    x: speed * angle.cos(),
+                    y: speed * angle.sin(),
+                },
+            ))
+            .remove::<Disabled>();
     }
 }
 
-/// Move stars and update their size/brightness
-pub fn move_stars(
-    mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &mut Sprite, &mut Star, &Velocity)>,
-    time: Res<Time>,
-    window_dims: Res<WindowDimensions>,
+/// Update star size/brightness as they move outward
+pub fn update_stars(
+    mut query: Query<(&Transform, &mut Sprite, &mut Star)>,
+    config: Res<GameConfig>,
 ) {
-    // Calculate max distance based on current window dimensions
-    let max_distance =
-        ((window_dims.width / 2.0).powi(2) + (window_dims.height / 2.0).powi(2)).sqrt();
+    let starfield = &config.starfield;
 
-    for (entity, mut transform, mut sprite, mut star, velocity) in query.iter_mut() {
-        // Move star
-        transform.translation.x += velocity.x * time.delta_secs();
-        transform.translation.y += velocity.y * time.delta_secs();
+    // Distance from the center to a corner of the playfield
+    let max_distance = Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT).length() / 2.0;
 
+    for (transform, mut sprite, mut star) in query.iter_mut() {
         // Calculate distance from center
         star.distance_from_center =
             (transform.translation.x.powi(2) + transform.translation.y.powi(2)).sqrt();
@@ -67,19 +86,12 @@ pub fn move_stars(
         let progress = (star.distance_from_center / max_distance).min(1.0);
 
         // Increase size as star moves outward
-        let size = STAR_MIN_SIZE + (STAR_MAX_SIZE - STAR_MIN_SIZE) * progress;
+        let size = starfield.min_size + (starfield.max_size - starfield.min_size) * progress;
         sprite.custom_size = Some(Vec2::new(size, size));
 
         // Increase brightness as star moves outward
-        let brightness =
-            STAR_MIN_BRIGHTNESS + (STAR_MAX_BRIGHTNESS - STAR_MIN_BRIGHTNESS) * progress;
+        let brightness = starfield.min_brightness
+            + (starfield.max_brightness - starfield.min_brightness) * progress;
         sprite.color = Color::srgb(brightness, brightness, brightness);
-
-        // Despawn if off-screen
-        if transform.translation.x.abs() > window_dims.width / 2.0 + 10.0
-            || transform.translation.y.abs() > window_dims.height / 2.0 + 10.0
-        {
-            commands.entity(entity).despawn();
-        }
     }
 }
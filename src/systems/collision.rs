@@ -1,80 +1,144 @@
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 
 use crate::components::*;
 use crate::constants::*;
+use crate::messages::*;
 use crate::resources::*;
-use crate::systems::explosion::spawn_explosion;
 
-/// Check for bullet-enemy collisions
+/// Rebuild the collision broadphase grid from every entity with a hitbox
+pub fn rebuild_collision_grid(
+    mut grid: ResMut<CollisionGrid>,
+    query: Query<(Entity, &Transform, &Hitbox)>,
+) {
+    grid.clear();
+
+    for (entity, transform, hitbox) in query.iter() {
+        grid.insert(entity, transform.translation.truncate(), hitbox.half_size);
+    }
+}
+
+/// Detect overlapping hitboxes whose layers match and report them as collisions
+pub fn detect_collisions(
+    grid: Res<CollisionGrid>,
+    query: Query<(Entity, &Transform, &Hitbox)>,
+    mut collisions: MessageWriter<Collision>,
+    mut candidates: Local<Vec<Entity>>,
+    mut claimed: Local<HashSet<Entity>>,
+) {
+    claimed.clear();
+
+    for (entity, transform, hitbox) in query.iter() {
+        if hitbox.mask == CollisionLayer::NONE {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        grid.query(position, hitbox.half_size, &mut candidates);
+
+        // Each entity resolves at most one hit per frame: the nearest target not already claimed
+        let hit = candidates
+            .iter()
+            .filter(|&&other| other != entity && !claimed.contains(&other))
+            .filter_map(|&other| query.get(other).ok())
+            .filter(|(_, other_transform, other_hitbox)| {
+                hitbox.mask.intersects(other_hitbox.layer)
+                    && hitbox.overlaps(
+                        position,
+                        other_hitbox,
+                        other_transform.translation.truncate(),
+                    )
+            })
+            .map(|(other, other_transform, _)| {
+                let other_position = other_transform.translation.truncate();
+                (other, position.distance(other_position), other_position)
+            })
+            // Ties go to the leftmost, then lowest target, so the hit depends
+            // only on positions and a replayed run resolves it the same way
+            .min_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then(a.2.x.total_cmp(&b.2.x))
+                    .then(a.2.y.total_cmp(&b.2.y))
+            });
+
+        if let Some((other, ..)) = hit {
+            claimed.insert(other);
+            collisions.write(Collision { entity, other });
+        }
+    }
+}
+
+/// Handle player bullets hitting enemies
 pub fn check_bullet_enemy_collision(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &Transform), With<Bullet>>,
-    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
-    mut game_state: ResMut<GameState>,
-    audio: Res<GameAudio>,
+    mut collisions: MessageReader<Collision>,
+    bullet_query: Query<(), With<Bullet>>,
+    enemy_query: Query<(&Transform, &EnemyKind, Has<Saucer>), With<Enemy>>,
+    mut pools: ResMut<EntityPools>,
+    mut enemy_killed: MessageWriter<EnemyKilled>,
 ) {
-    for (bullet_entity, bullet_transform) in bullet_query.iter() {
-        for (enemy_entity, enemy_transform) in enemy_query.iter() {
-            let distance = bullet_transform
-                .translation
-                .distance(enemy_transform.translation);
-
-            if distance < (BULLET_SIZE + ENEMY_SIZE) / 2.0 {
-                commands.entity(bullet_entity).despawn();
-                commands.entity(enemy_entity).despawn();
-                game_state.score += 10;
-
-                // Spawn explosion with orange/yellow color
-                spawn_explosion(
-                    &mut commands,
-                    enemy_transform.translation,
-                    Color::srgb(1.0, 0.6, 0.0),
-                );
-
-                // Play enemy destroyed sound
-                commands.spawn((
-                    AudioPlayer::new(audio.enemy_destroyed.clone()),
-                    PlaybackSettings::DESPAWN,
-                ));
-            }
+    for collision in collisions.read() {
+        let Ok((enemy_transform, kind, saucer)) = enemy_query.get(collision.other) else {
+            continue;
+        };
+        if !bullet_query.contains(collision.entity) {
+            continue;
         }
+
+        pools.bullets.release(&mut commands, collision.entity);
+        commands.entity(collision.other).despawn();
+
+        enemy_killed.write(EnemyKilled {
+            position: enemy_transform.translation,
+            points: ENEMY_POINTS,
+            kind: *kind,
+            saucer,
+        });
     }
 }
 
-/// Check for enemy bullet-player collisions
+/// Handle enemy bullets hitting the player: each hit costs a life, and the hit
+/// that takes the last one destroys the ship and ends the run
+#[allow(clippy::too_many_arguments)]
 pub fn check_bullet_player_collision(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &Transform), With<EnemyBullet>>,
-    player_query: Query<(Entity, &Transform), With<Player>>,
-    mut next_state: ResMut<NextState<GamePhase>>,
-    audio: Res<GameAudio>,
+    mut collisions: MessageReader<Collision>,
+    bullet_query: Query<Entity, With<EnemyBullet>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut game_state: ResMut<GameState>,
+    mut pools: ResMut<EntityPools>,
+    mut player_hit: MessageWriter<PlayerHit>,
+    mut run_ended: MessageWriter<RunEnded>,
 ) {
-    if let Ok((player_entity, player_transform)) = player_query.single() {
-        for (bullet_entity, bullet_transform) in bullet_query.iter() {
-            let distance = bullet_transform
-                .translation
-                .distance(player_transform.translation);
-
-            if distance < (BULLET_SIZE + PLAYER_SIZE) / 2.0 {
-                commands.entity(bullet_entity).despawn();
-                commands.entity(player_entity).despawn();
-
-                // Spawn explosion with red/orange color
-                spawn_explosion(
-                    &mut commands,
-                    player_transform.translation,
-                    Color::srgb(1.0, 0.3, 0.0),
-                );
-
-                // Play player destroyed sound
-                commands.spawn((
-                    AudioPlayer::new(audio.player_destroyed.clone()),
-                    PlaybackSettings::DESPAWN,
-                ));
-
-                next_state.set(GamePhase::GameOver);
-                break;
+    let mut cleared = false;
+    for collision in collisions.read() {
+        let Ok(player_transform) = player_query.get(collision.other) else {
+            continue;
+        };
+        if cleared || !bullet_query.contains(collision.entity) {
+            continue;
+        }
+
+        player_hit.write(PlayerHit {
+            position: player_transform.translation,
+        });
+        game_state.lives = game_state.lives.saturating_sub(1);
+
+        if game_state.lives > 0 {
+            // The ship survives on a spare life, and the shots in flight are
+            // cleared so it is not hit again straight away
+            for bullet in bullet_query.iter() {
+                pools.enemy_bullets.release(&mut commands, bullet);
             }
+            cleared = true;
+            continue;
         }
+
+        pools.enemy_bullets.release(&mut commands, collision.entity);
+        commands.entity(collision.other).despawn();
+
+        run_ended.write(RunEnded {
+            outcome: RunOutcome::Shot,
+        });
     }
 }
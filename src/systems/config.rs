@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+use crate::config::*;
+use crate::resources::*;
+
+/// Copy the game configuration asset into the `GameConfig` resource whenever it
+/// finishes loading or changes on disk
+pub fn apply_game_config(
+    mut asset_events: MessageReader<AssetEvent<GameConfig>>,
+    configs: Res<Assets<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    mut config: ResMut<GameConfig>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    for event in asset_events.read() {
+        if (event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = configs.get(&handle.0)
+        {
+            *config = loaded.clone();
+            high_scores.set_capacity(config.high_scores.table_size);
+            info!("Applied game config from {GAME_CONFIG_PATH}");
+        }
+    }
+}
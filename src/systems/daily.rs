@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::daily::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::storage::*;
+
+/// Whether the run being played is a daily challenge
+pub fn daily_run(run_mode: Res<RunMode>) -> bool {
+    matches!(*run_mode, RunMode::Daily(_))
+}
+
+/// Seed the gameplay RNG for a new run: from the date for the daily challenge,
+/// from the replayed run's seed when replaying, at random otherwise
+pub fn seed_run(
+    run_mode: Res<RunMode>,
+    replay: Option<Res<InputReplay>>,
+    mut rng: ResMut<GameRng>,
+    mut input_log: ResMut<InputLog>,
+) {
+    *rng = match (*run_mode, replay) {
+        (_, Some(replay)) => GameRng::seeded(replay.seed),
+        (RunMode::Daily(date), None) => GameRng::seeded(daily_seed(date)),
+        (RunMode::Standard, None) => GameRng::default(),
+    };
+    *input_log = InputLog::default();
+}
+
+/// Add this tick's player input to the input log of the daily challenge
+pub fn record_daily_input(tick_input: Res<TickInput>, mut input_log: ResMut<InputLog>) {
+    input_log.record(*tick_input);
+}
+
+/// Feed the next tick of a replayed input log to gameplay, in place of the
+/// player's controls. Once the log is used up the ship stands still.
+pub fn replay_tick_input(mut replay: ResMut<InputReplay>, mut tick_input: ResMut<TickInput>) {
+    *tick_input = replay.next_input().unwrap_or_default();
+}
+
+/// Add the result of a finished daily challenge to the daily table and save it
+pub fn record_daily_result(
+    mut run_ended: MessageReader<RunEnded>,
+    run_mode: Res<RunMode>,
+    game_state: Res<GameState>,
+    current_wave: Res<CurrentWave>,
+    input_log: Res<InputLog>,
+    storage: Res<Storage>,
+    mut daily_scores: ResMut<DailyScores>,
+) {
+    if run_ended.read().count() == 0 {
+        return;
+    }
+    let RunMode::Daily(date) = *run_mode else {
+        return;
+    };
+
+    let code = DailyCode::new(date, game_state.score, &input_log);
+    info!("Daily challenge {date} finished, code {code}");
+    daily_scores.add(DailyEntry {
+        date,
+        score: game_state.score,
+        wave: current_wave.index + 1,
+        code: code.to_string(),
+        input_log: input_log.clone(),
+        is_current: true,
+    });
+    storage.save(&*daily_scores);
+}
@@ -1,16 +1,28 @@
+use bevy::ecs::entity_disabling::Disabled;
 use bevy::prelude::*;
 use rand::Rng;
 
 use crate::components::*;
+use crate::config::*;
 use crate::constants::*;
+use crate::messages::*;
 use crate::resources::*;
+use crate::waves::*;
 
-/// Move enemies in formation
+/// Steer enemies in formation; positions are integrated by `integrate_motion`
 pub fn move_enemies(
     mut query: Query<(&mut Transform, &mut Velocity, &Enemy)>,
     mut game_state: ResMut<GameState>,
-    time: Res<Time>,
+    current_wave: Res<CurrentWave>,
+    difficulty: Res<Difficulty>,
 ) {
+    // Speed up as the wave is destroyed
+    let speed = current_wave
+        .definition
+        .speed
+        .speed(current_wave.progress(query.iter().len()))
+        * difficulty.enemy_speed_scale();
+
     let mut should_move_down = false;
     let mut reverse_direction = false;
 
@@ -30,8 +42,7 @@ pub fn move_enemies(
 
     // Move enemies
     for (mut transform, mut velocity, _) in query.iter_mut() {
-        velocity.x = ENEMY_SPEED * game_state.enemy_direction;
-        transform.translation.x += velocity.x * time.delta_secs();
+        velocity.x = speed * game_state.enemy_direction;
 
         if should_move_down {
             transform.translation.y -= 20.0;
@@ -40,62 +51,111 @@ pub fn move_enemies(
 }
 
 /// Handle enemy shooting
+#[allow(clippy::too_many_arguments)]
 pub fn enemy_shoot(
     mut commands: Commands,
     query: Query<&Transform, With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
     mut game_state: ResMut<GameState>,
     time: Res<Time>,
-    textures: Res<GameTextures>,
-    audio: Res<GameAudio>,
+    mut pools: ResMut<EntityPools>,
+    config: Res<GameConfig>,
+    current_wave: Res<CurrentWave>,
+    difficulty: Res<Difficulty>,
+    mut rng: ResMut<GameRng>,
+    mut shot_fired: MessageWriter<ShotFired>,
 ) {
     game_state.enemy_shoot_timer += time.delta_secs();
 
-    if game_state.enemy_shoot_timer >= ENEMY_SHOOT_INTERVAL {
+    let interval = current_wave.definition.fire.interval * difficulty.fire_interval_scale();
+    if game_state.enemy_shoot_timer >= interval {
         game_state.enemy_shoot_timer = 0.0;
 
-        // Pick a random enemy to shoot
-        let enemies: Vec<&Transform> = query.iter().collect();
-        if !enemies.is_empty() {
-            let mut rng = rand::rng();
-            let index = rng.random_range(0..enemies.len());
-            let enemy_transform = enemies[index];
+        // Pick the shooting enemy according to the wave's fire policy.
+        // The shot is skipped while every pooled enemy bullet is in flight.
+        // Sorted by position so the choice depends only on the RNG state,
+        // not on entity order, which differs after a run is restored
+        let mut enemies: Vec<&Transform> = query.iter().collect();
+        enemies.sort_by(|a, b| {
+            a.translation
+                .y
+                .total_cmp(&b.translation.y)
+                .then(a.translation.x.total_cmp(&b.translation.x))
+        });
+        if let Some(enemy_transform) = select_shooter(
+            &enemies,
+            current_wave.definition.fire.shooter,
+            player_query.single().ok(),
+            &mut rng,
+        ) && let Some(bullet) = pools.enemy_bullets.acquire()
+        {
+            commands
+                .entity(bullet)
+                .insert((
+                    Transform::from_xyz(
+                        enemy_transform.translation.x,
+                        enemy_transform.translation.y - ENEMY_SIZE / 2.0,
+                        0.0,
+                    ),
+                    Velocity {
+                        x: 0.0,
+                        y: -config.bullet.speed,
+                    },
+                ))
+                .remove::<Disabled>();
 
-            commands.spawn((
-                Sprite {
-                    image: textures.enemy_bullet.clone(),
-                    custom_size: Some(Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0)),
-                    ..default()
-                },
-                Transform::from_xyz(
-                    enemy_transform.translation.x,
-                    enemy_transform.translation.y - ENEMY_SIZE / 2.0,
-                    0.0,
-                ),
-                EnemyBullet,
-                Velocity {
-                    x: 0.0,
-                    y: -BULLET_SPEED,
-                },
-            ));
+            shot_fired.write(ShotFired {
+                shooter: Shooter::Enemy,
+            });
+        }
+    }
+}
+
+/// Choose which enemy fires next
+fn select_shooter<'a>(
+    enemies: &[&'a Transform],
+    selection: ShooterSelection,
+    player: Option<&Transform>,
+    rng: &mut GameRng,
+) -> Option<&'a Transform> {
+    // Enemies with no other enemy below them in the same column
+    let front: Vec<&Transform> = enemies
+        .iter()
+        .filter(|enemy| {
+            !enemies.iter().any(|other| {
+                (other.translation.x - enemy.translation.x).abs() < ENEMY_SIZE / 2.0
+                    && other.translation.y < enemy.translation.y
+            })
+        })
+        .copied()
+        .collect();
 
-            // Play enemy shoot sound
-            commands.spawn((
-                AudioPlayer::new(audio.enemy_shoot.clone()),
-                PlaybackSettings::DESPAWN,
-            ));
+    match (selection, player) {
+        (ShooterSelection::Random, _) | (ShooterSelection::Aimed, None) if !enemies.is_empty() => {
+            Some(enemies[rng.random_range(0..enemies.len())])
+        }
+        (ShooterSelection::Front, _) if !front.is_empty() => {
+            Some(front[rng.random_range(0..front.len())])
         }
+        (ShooterSelection::Aimed, Some(player)) => front.into_iter().min_by(|a, b| {
+            let a_distance = (a.translation.x - player.translation.x).abs();
+            let b_distance = (b.translation.x - player.translation.x).abs();
+            a_distance.total_cmp(&b_distance)
+        }),
+        _ => None,
     }
 }
 
 /// Check if enemies reached the bottom
 pub fn check_enemy_reached_bottom(
     query: Query<&Transform, With<Enemy>>,
-    mut next_state: ResMut<NextState<GamePhase>>,
-    window_dims: Res<WindowDimensions>,
+    mut run_ended: MessageWriter<RunEnded>,
 ) {
     for transform in query.iter() {
-        if transform.translation.y < -window_dims.height / 2.0 + 50.0 {
-            next_state.set(GamePhase::GameOver);
+        if transform.translation.y < -PLAYFIELD_HEIGHT / 2.0 + 50.0 {
+            run_ended.write(RunEnded {
+                outcome: RunOutcome::Invaded,
+            });
             break;
         }
     }
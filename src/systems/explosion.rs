@@ -1,22 +1,31 @@
+use bevy::ecs::entity_disabling::Disabled;
 use bevy::prelude::*;
 use rand::Rng;
 
 use crate::components::*;
-use crate::constants::*;
+use crate::config::*;
+use crate::messages::*;
+use crate::resources::*;
 
 /// Spawn an explosion at a position
-pub fn spawn_explosion(commands: &mut Commands, position: Vec3, color: Color) {
+pub fn spawn_explosion(
+    commands: &mut Commands,
+    pool: &mut EntityPool,
+    config: &ExplosionConfig,
+    position: Vec3,
+    color: Color,
+) {
     let mut rng = rand::rng();
 
-    for _ in 0..EXPLOSION_PARTICLES {
+    for _ in 0..config.particles {
         // Random angle for particle direction
         let angle = rng.random_range(0.0..std::f32::consts::TAU);
 
         // Random speed variation
-        let speed = EXPLOSION_PARTICLE_SPEED * rng.random_range(0.5..1.5);
+        let speed = config.particle_speed * rng.random_range(0.5..1.5);
 
         // Random particle size
-        let size = EXPLOSION_PARTICLE_SIZE * rng.random_range(0.5..1.5);
+        let size = config.particle_size * rng.random_range(0.5..1.5);
 
         // Slight color variation
         let color_variation = rng.random_range(0.8..1.2);
@@ -27,47 +36,71 @@ pub fn spawn_explosion(commands: &mut Commands, position: Vec3, color: Color) {
             (base_color.blue * color_variation).min(1.0),
         );
 
-        commands.spawn((
-            Sprite {
-                color: particle_color,
-                custom_size: Some(Vec2::new(size, size)),
-                ..default()
-            },
-            Transform::from_xyz(position.x, position.y, position.z + 0.1),
-            ExplosionParticle {
-                lifetime: 0.0,
-                max_lifetime: EXPLOSION_LIFETIME * rng.random_range(0.8..1.2),
-            },
-            Velocity {
-                x: speed * angle.cos(),
-                y: speed * angle.sin(),
-            },
-        ));
+        // Smaller explosions while the particle pool is exhausted
+        let Some(particle) = pool.acquire() else {
+            return;
+        };
+
+        commands
+            .entity(particle)
+            .insert((
+                Sprite {
+                    color: particle_color,
+                    custom_size: Some(Vec2::new(size, size)),
+                    ..default()
+                },
+                Transform::from_xyz(position.x, position.y, position.z + 0.1),
+                Lifetime::new(config.lifetime * rng.random_range(0.8..1.2)),
+                Velocity {
+                    x: speed * angle.cos(),
+                    y: speed * angle.sin(),
+                },
+                Acceleration {
+                    x: 0.0,
+                    y: -config.particle_gravity,
+                },
+                Drag(config.particle_drag),
+            ))
+            .remove::<Disabled>();
     }
 }
 
-/// Update explosion particles
-pub fn update_explosions(
+/// Spawn explosions for destroyed enemies and the player
+pub fn spawn_gameplay_explosions(
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &mut Transform,
-        &mut Sprite,
-        &mut ExplosionParticle,
-        &Velocity,
-    )>,
-    time: Res<Time>,
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut player_hit: MessageReader<PlayerHit>,
+    mut pools: ResMut<EntityPools>,
+    config: Res<GameConfig>,
 ) {
-    for (entity, mut transform, mut sprite, mut particle, velocity) in query.iter_mut() {
-        // Update lifetime
-        particle.lifetime += time.delta_secs();
+    // Orange/yellow for enemies
+    for kill in enemy_killed.read() {
+        spawn_explosion(
+            &mut commands,
+            &mut pools.explosion_particles,
+            &config.explosion,
+            kill.position,
+            Color::srgb(1.0, 0.6, 0.0),
+        );
+    }
 
-        // Move particle
-        transform.translation.x += velocity.x * time.delta_secs();
-        transform.translation.y += velocity.y * time.delta_secs();
+    // Red/orange for the player
+    for hit in player_hit.read() {
+        spawn_explosion(
+            &mut commands,
+            &mut pools.explosion_particles,
+            &config.explosion,
+            hit.position,
+            Color::srgb(1.0, 0.3, 0.0),
+        );
+    }
+}
 
+/// Fade explosion particles out over their lifetime
+pub fn update_explosions(mut query: Query<(&mut Sprite, &Lifetime), With<ExplosionParticle>>) {
+    for (mut sprite, lifetime) in query.iter_mut() {
         // Fade out based on lifetime
-        let alpha = 1.0 - (particle.lifetime / particle.max_lifetime);
+        let alpha = 1.0 - lifetime.fraction();
         let current_color = sprite.color.to_srgba();
         sprite.color = Color::srgba(
             current_color.red,
@@ -75,10 +108,5 @@ pub fn update_explosions(
             current_color.blue,
             alpha,
         );
-
-        // Despawn if lifetime exceeded
-        if particle.lifetime >= particle.max_lifetime {
-            commands.entity(entity).despawn();
-        }
     }
 }
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use chrono::Local;
+
+use crate::components::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::systems::input::PlayerControls;
+use crate::systems::touch::spawn_tap_row;
+
+/// Keys that type a letter directly into the initials
+const LETTER_KEYS: [(KeyCode, char); 26] = [
+    (KeyCode::KeyA, 'A'),
+    (KeyCode::KeyB, 'B'),
+    (KeyCode::KeyC, 'C'),
+    (KeyCode::KeyD, 'D'),
+    (KeyCode::KeyE, 'E'),
+    (KeyCode::KeyF, 'F'),
+    (KeyCode::KeyG, 'G'),
+    (KeyCode::KeyH, 'H'),
+    (KeyCode::KeyI, 'I'),
+    (KeyCode::KeyJ, 'J'),
+    (KeyCode::KeyK, 'K'),
+    (KeyCode::KeyL, 'L'),
+    (KeyCode::KeyM, 'M'),
+    (KeyCode::KeyN, 'N'),
+    (KeyCode::KeyO, 'O'),
+    (KeyCode::KeyP, 'P'),
+    (KeyCode::KeyQ, 'Q'),
+    (KeyCode::KeyR, 'R'),
+    (KeyCode::KeyS, 'S'),
+    (KeyCode::KeyT, 'T'),
+    (KeyCode::KeyU, 'U'),
+    (KeyCode::KeyV, 'V'),
+    (KeyCode::KeyW, 'W'),
+    (KeyCode::KeyX, 'X'),
+    (KeyCode::KeyY, 'Y'),
+    (KeyCode::KeyZ, 'Z'),
+];
+
+/// Setup the initials entry screen
+pub fn setup_name_entry_screen(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut initials: ResMut<InitialsEntry>,
+) {
+    *initials = InitialsEntry::default();
+
+    // Title text
+    commands.spawn((
+        Text::new(localization.text("name_entry.title")),
+        TextFont {
+            font_size: 50.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.84, 0.0)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::NameEntry),
+    ));
+
+    // Score text
+    commands.spawn((
+        Text::new(localization.format(
+            "hud.score",
+            &[("score", &localization.number(game_state.score.into()))],
+        )),
+        TextFont {
+            font_size: 35.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(180.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::NameEntry),
+    ));
+
+    // Initials, filled in by update_initials_display
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 60.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.0, 1.0, 0.5)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(270.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        InitialsText,
+        DespawnOnExit(GamePhase::NameEntry),
+    ));
+
+    // Instructions text
+    commands.spawn((
+        Text::new(settings.bindings.hint("name_entry.hint", &localization)),
+        BindingHint("name_entry.hint"),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(400.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::NameEntry),
+    ));
+
+    // Buttons for entering initials without a keyboard
+    spawn_tap_row(
+        &mut commands,
+        470.0,
+        &[
+            ("<".to_string(), TapTarget::Action(Action::MenuLeft)),
+            (
+                localization.text("name_entry.up"),
+                TapTarget::Action(Action::MenuUp),
+            ),
+            (
+                localization.text("name_entry.down"),
+                TapTarget::Action(Action::MenuDown),
+            ),
+            (">".to_string(), TapTarget::Action(Action::MenuRight)),
+            (
+                localization.text("name_entry.ok"),
+                TapTarget::Action(Action::Confirm),
+            ),
+        ],
+        GamePhase::NameEntry,
+    );
+}
+
+/// Edit the initials and record the high score on confirm. Letters are picked
+/// with the menu actions, so a gamepad or on-screen buttons can enter them, or
+/// typed on the keyboard.
+#[allow(clippy::too_many_arguments)]
+pub fn name_entry_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    controls: PlayerControls,
+    mut initials: ResMut<InitialsEntry>,
+    mut high_scores: ResMut<HighScores>,
+    game_state: Res<GameState>,
+    current_wave: Res<CurrentWave>,
+    difficulty: Res<Difficulty>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    // Alt+Enter toggles fullscreen instead
+    let alt = keyboard_input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let enter = keyboard_input.just_pressed(KeyCode::Enter) && !alt;
+    if enter || controls.just_pressed(Action::Confirm) {
+        high_scores.add(ScoreEntry {
+            score: game_state.score,
+            name: initials.name(),
+            date: Some(Local::now().date_naive()),
+            wave: current_wave.index + 1,
+            difficulty: *difficulty,
+            is_current: true,
+        });
+        next_state.set(GamePhase::GameOver);
+        return;
+    }
+
+    if controls.just_pressed(Action::MenuUp) {
+        initials.cycle(1);
+    }
+    if controls.just_pressed(Action::MenuDown) {
+        initials.cycle(-1);
+    }
+    if controls.just_pressed(Action::MenuLeft) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        initials.move_cursor(-1);
+    }
+    if controls.just_pressed(Action::MenuRight) {
+        initials.move_cursor(1);
+    }
+    for (key, letter) in LETTER_KEYS {
+        if keyboard_input.just_pressed(key) {
+            initials.type_letter(letter);
+        }
+    }
+}
+
+/// Show the initials with the letter being edited in brackets
+pub fn update_initials_display(
+    initials: Res<InitialsEntry>,
+    mut query: Query<&mut Text, With<InitialsText>>,
+) {
+    if !initials.is_changed() {
+        return;
+    }
+
+    let display: Vec<String> = initials
+        .letters
+        .iter()
+        .enumerate()
+        .map(|(i, letter)| {
+            if i == initials.cursor {
+                format!("[{letter}]")
+            } else {
+                format!(" {letter} ")
+            }
+        })
+        .collect();
+
+    for mut text in query.iter_mut() {
+        **text = display.join(" ");
+    }
+}
@@ -0,0 +1,249 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::window::CursorMoved;
+
+use crate::components::*;
+use crate::config::*;
+use crate::constants::*;
+use crate::input::*;
+use crate::resources::*;
+use crate::settings::*;
+
+/// Give newly connected gamepads to the first player without one, and free the
+/// slot of a gamepad that was unplugged
+pub fn assign_gamepads(
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut assignment: ResMut<GamepadAssignment>,
+) {
+    for slot in 0..assignment.players.len() {
+        if let Some(gamepad) = assignment.players[slot]
+            && !gamepads.contains(gamepad)
+        {
+            assignment.players[slot] = None;
+            info!("Gamepad {gamepad} of player {} disconnected", slot + 1);
+        }
+    }
+
+    for gamepad in gamepads.iter() {
+        if assignment.slot_of(gamepad).is_none()
+            && let Some(slot) = assignment.players.iter().position(Option::is_none)
+        {
+            assignment.players[slot] = Some(gamepad);
+            info!("Gamepad {gamepad} assigned to player {}", slot + 1);
+        }
+    }
+}
+
+/// Read the mouse, touches and on-screen buttons into `PointerInput`. Touch
+/// mode turns on with the first touch; mouse mode with cursor movement, and off
+/// again with a key press.
+pub fn read_pointer(
+    mut pointer: ResMut<PointerInput>,
+    mut cursor_moved: MessageReader<CursorMoved>,
+    touches: Res<Touches>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    buttons: Query<(&Interaction, &TapButton)>,
+    window_dims: Res<WindowDimensions>,
+) {
+    if touches.any_just_pressed() && pointer.mode != PointerMode::Touch {
+        pointer.mode = PointerMode::Touch;
+        pointer.target_x = None;
+        info!("Touch input detected, showing touch controls");
+    }
+    let cursor = cursor_moved.read().last().map(|moved| moved.position);
+    if pointer.mode != PointerMode::Touch {
+        if cursor.is_some() {
+            pointer.mode = PointerMode::Mouse;
+        } else if keyboard.get_just_pressed().next().is_some() {
+            pointer.mode = PointerMode::Off;
+            pointer.target_x = None;
+        }
+    }
+
+    let mut held: Vec<TapTarget> = buttons
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0)
+        .collect();
+    // A click fires, unless it lands on an on-screen button
+    if pointer.mode == PointerMode::Mouse && held.is_empty() && mouse.pressed(MouseButton::Left) {
+        held.push(TapTarget::Action(Action::Fire));
+    }
+    pointer.tapped = held
+        .iter()
+        .filter(|target| !pointer.held.contains(target))
+        .copied()
+        .collect();
+    pointer.held = held;
+
+    match pointer.mode {
+        PointerMode::Off => {}
+        PointerMode::Mouse => {
+            if let Some(cursor) = cursor {
+                pointer.target_x = Some(window_dims.to_playfield(cursor).x);
+            }
+        }
+        PointerMode::Touch => {
+            // The button bar runs along the bottom of the playfield
+            let bar_top = -PLAYFIELD_HEIGHT / 2.0 + TOUCH_BAR_HEIGHT;
+            pointer.target_x = touches
+                .iter()
+                .find(|touch| window_dims.to_playfield(touch.start_position()).y > bar_top)
+                .map(|touch| window_dims.to_playfield(touch.position()).x);
+        }
+    }
+}
+
+/// Scale a stick axis so that the deadzone reads as 0.0 and the rest of the
+/// travel still covers the full range
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+    }
+}
+
+/// Keyboard, mouse, touch and gamepad input of the first player, read as actions
+#[derive(SystemParam)]
+pub struct PlayerControls<'w, 's> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    assignment: Res<'w, GamepadAssignment>,
+    settings: Res<'w, Settings>,
+    pointer: Res<'w, PointerInput>,
+}
+
+impl PlayerControls<'_, '_> {
+    /// Gamepad of the first player, if one is assigned
+    pub fn gamepad(&self) -> Option<&Gamepad> {
+        self.assignment.players[0].and_then(|entity| self.gamepads.get(entity).ok())
+    }
+
+    /// How far one binding is pressed, from 0.0 to 1.0
+    fn binding_value(&self, binding: Binding) -> f32 {
+        match binding {
+            Binding::Key(key) => f32::from(u8::from(self.keyboard.pressed(key))),
+            Binding::Mouse(button) => f32::from(u8::from(self.mouse.pressed(button))),
+            Binding::Gamepad(button) => self.gamepad().map_or(0.0, |gamepad| {
+                if gamepad.pressed(button) {
+                    1.0
+                } else {
+                    gamepad.get(button).unwrap_or(0.0)
+                }
+            }),
+            Binding::Stick { axis, positive } => self.gamepad().map_or(0.0, |gamepad| {
+                let value = apply_deadzone(
+                    gamepad.get(axis).unwrap_or(0.0),
+                    self.settings.gamepad_deadzone,
+                );
+                if positive {
+                    value.max(0.0)
+                } else {
+                    (-value).max(0.0)
+                }
+            }),
+        }
+    }
+
+    fn binding_just_pressed(&self, binding: Binding) -> bool {
+        match binding {
+            // Alt+Enter toggles fullscreen instead
+            Binding::Key(KeyCode::Enter)
+                if self
+                    .keyboard
+                    .any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) =>
+            {
+                false
+            }
+            Binding::Key(key) => self.keyboard.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+            Binding::Gamepad(button) => self
+                .gamepad()
+                .is_some_and(|gamepad| gamepad.just_pressed(button)),
+            // Sticks are analog and only used for held actions
+            Binding::Stick { .. } => false,
+        }
+    }
+
+    /// How far `action` is pressed, from 0.0 to 1.0, through its strongest binding
+    /// or an on-screen button
+    pub fn value(&self, action: Action) -> f32 {
+        if self.pointer.is_held(TapTarget::Action(action)) {
+            return 1.0;
+        }
+        self.settings
+            .bindings
+            .get(action)
+            .iter()
+            .map(|binding| self.binding_value(*binding))
+            .fold(0.0, f32::max)
+    }
+
+    /// `action` was triggered this frame by any of its bindings or an on-screen
+    /// button
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pointer.just_tapped(TapTarget::Action(action))
+            || self
+                .settings
+                .bindings
+                .get(action)
+                .iter()
+                .any(|binding| self.binding_just_pressed(*binding))
+    }
+
+    /// Horizontal movement from -1.0 (left) to 1.0 (right)
+    pub fn movement(&self) -> f32 {
+        self.value(Action::MoveRight) - self.value(Action::MoveLeft)
+    }
+
+    /// Movement of a ship at playfield `x` that moves at most `step` this frame:
+    /// bound inputs first, otherwise towards the mouse or touch without
+    /// overshooting it
+    pub fn steering(&self, x: f32, step: f32) -> f32 {
+        let movement = self.movement();
+        match self.pointer.target_x {
+            Some(target) if movement == 0.0 && step > 0.0 => ((target - x) / step).clamp(-1.0, 1.0),
+            _ => movement,
+        }
+    }
+}
+
+/// Hold on to Fire presses of this frame until the next gameplay tick
+pub fn latch_fire_press(controls: PlayerControls, mut pending_fire: ResMut<PendingFire>) {
+    if controls.just_pressed(Action::Fire) {
+        pending_fire.0 = true;
+    }
+}
+
+/// Read the player's controls into the input of this gameplay tick
+pub fn read_tick_input(
+    controls: PlayerControls,
+    mut pending_fire: ResMut<PendingFire>,
+    mut tick_input: ResMut<TickInput>,
+    player: Query<&Transform, With<Player>>,
+    settings: Res<Settings>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    let steering = player.single().map_or(0.0, |transform| {
+        controls.steering(
+            transform.translation.x,
+            config.player.speed * time.delta_secs(),
+        )
+    });
+    *tick_input = TickInput {
+        steering: TickInput::quantize_steering(steering),
+        fire: std::mem::take(&mut pending_fire.0),
+        autofire: settings.autofire && controls.value(Action::Fire) > 0.5,
+    };
+}
+
+/// Start a run with no input carried over from before it
+pub fn reset_tick_input(mut tick_input: ResMut<TickInput>, mut pending_fire: ResMut<PendingFire>) {
+    *tick_input = TickInput::default();
+    pending_fire.0 = false;
+}
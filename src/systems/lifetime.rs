@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::constants::*;
+use crate::resources::*;
+use crate::systems::pool::despawn_or_release;
+
+/// Advance lifetimes and remove entities whose lifetime has run out
+pub fn expire_lifetimes(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Lifetime, Option<&Pooled>)>,
+    mut pools: ResMut<EntityPools>,
+    time: Res<Time>,
+) {
+    for (entity, mut lifetime, pooled) in query.iter_mut() {
+        lifetime.elapsed += time.delta_secs();
+
+        if lifetime.is_expired() {
+            despawn_or_release(&mut commands, &mut pools, entity, pooled);
+        }
+    }
+}
+
+/// Remove entities that left the playfield
+pub fn despawn_offscreen(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, &OffscreenDespawn, Option<&Pooled>)>,
+    mut pools: ResMut<EntityPools>,
+) {
+    for (entity, transform, offscreen, pooled) in query.iter() {
+        if transform.translation.x.abs() > PLAYFIELD_WIDTH / 2.0 + offscreen.margin
+            || transform.translation.y.abs() > PLAYFIELD_HEIGHT / 2.0 + offscreen.margin
+        {
+            despawn_or_release(&mut commands, &mut pools, entity, pooled);
+        }
+    }
+}
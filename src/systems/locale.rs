@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::locale::*;
+use crate::resources::*;
+use crate::settings::*;
+
+/// Switch the UI text to the language picked in the settings once its
+/// translation has loaded, and pick up changes to it on disk
+pub fn apply_language(
+    mut asset_events: MessageReader<AssetEvent<Locale>>,
+    locales: Res<Assets<Locale>>,
+    handles: Res<LocaleHandles>,
+    settings: Res<Settings>,
+    mut localization: ResMut<Localization>,
+) {
+    let handle = LANGUAGES
+        .iter()
+        .position(|language| language.code == settings.language)
+        .and_then(|index| handles.0.get(index));
+    let reloaded = asset_events.read().any(|event| {
+        handle.is_some_and(|handle| {
+            event.is_loaded_with_dependencies(handle) || event.is_modified(handle)
+        })
+    });
+    if localization.language == settings.language && !reloaded {
+        return;
+    }
+
+    // Until the translation has loaded the previous language stays
+    if let Some(locale) = handle.and_then(|handle| locales.get(handle)) {
+        localization.set(&settings.language, locale.clone());
+        info!("Switched the UI text to '{}'", settings.language);
+    }
+}
+
+/// Show every `Localized` text in the current language
+pub fn refresh_localized_texts(
+    localization: Res<Localization>,
+    mut query: Query<(&Localized, &mut Text)>,
+) {
+    for (localized, mut text) in query.iter_mut() {
+        **text = localization.text(localized.0);
+    }
+}
+
+/// Show every `BindingHint` in the current language with the current bindings
+pub fn refresh_binding_hints(
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    mut query: Query<(&BindingHint, &mut Text)>,
+) {
+    for (hint, mut text) in query.iter_mut() {
+        **text = settings.bindings.hint(hint.0, &localization);
+    }
+}
+
+/// Start loading the fonts of the current language
+pub fn load_locale_fonts(
+    localization: Res<Localization>,
+    asset_server: Res<AssetServer>,
+    mut font: ResMut<LocaleFont>,
+) {
+    font.chain = localization
+        .fonts()
+        .map(|path| asset_server.load(path.to_string()))
+        .collect();
+}
+
+/// Use the first font of the chain that has loaded, and Bevy's built-in font
+/// while none has or when the language needs none
+pub fn select_locale_font(asset_server: Res<AssetServer>, mut font: ResMut<LocaleFont>) {
+    let available = font
+        .chain
+        .iter()
+        .find(|handle| asset_server.is_loaded_with_dependencies(*handle))
+        .cloned()
+        .unwrap_or_default();
+    if font.current != available {
+        font.current = available;
+    }
+}
+
+/// Draw every text in the font of the current language
+pub fn apply_locale_font(font: Res<LocaleFont>, mut query: Query<&mut TextFont>) {
+    for mut text_font in query.iter_mut() {
+        if text_font.font != font.current {
+            text_font.font = font.current.clone();
+        }
+    }
+}
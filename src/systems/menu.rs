@@ -0,0 +1,191 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::daily::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::systems::input::*;
+
+const MENU_FONT_SIZE: f32 = 24.0;
+
+/// Spawn a centered menu of `items`, placed by the `top` or `bottom` of
+/// `placement`, starting on `selected`. The items are labelled by
+/// `update_menu_display`. The menu is despawned when `state` is left.
+pub fn spawn_menu<S: States>(
+    commands: &mut Commands,
+    placement: Node,
+    items: Vec<MenuAction>,
+    selected: usize,
+    state: S,
+) {
+    let count = items.len();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(2.0),
+                ..placement
+            },
+            Menu { items, selected },
+            DespawnOnExit(state),
+        ))
+        .with_children(|menu| {
+            for index in 0..count {
+                menu.spawn((
+                    Button,
+                    Text::new(""),
+                    TextFont {
+                        font_size: MENU_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    Node {
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(1.0)),
+                        ..default()
+                    },
+                    MenuItem(index),
+                ));
+            }
+        });
+}
+
+/// Move the selection of the open menu and report confirmed and changed items.
+/// Hovering an item with the mouse selects it, clicking or tapping confirms it.
+pub fn menu_input(
+    controls: PlayerControls,
+    mut menus: Query<&mut Menu>,
+    items: Query<(&MenuItem, &Interaction, &ChildOf), Changed<Interaction>>,
+    mut activated: MessageWriter<MenuActivated>,
+) {
+    for (item, interaction, child_of) in items.iter() {
+        let Ok(mut menu) = menus.get_mut(child_of.parent()) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Hovered if menu.selected != item.0 => menu.selected = item.0,
+            Interaction::Pressed => {
+                menu.selected = item.0;
+                activated.write(MenuActivated {
+                    action: menu.selected_action(),
+                    step: 0,
+                });
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(mut menu) = menus.single_mut() else {
+        return;
+    };
+    let step = if controls.just_pressed(Action::MenuLeft) {
+        Some(-1)
+    } else if controls.just_pressed(Action::MenuRight) {
+        Some(1)
+    } else if controls.just_pressed(Action::Confirm) {
+        Some(0)
+    } else {
+        None
+    };
+
+    if controls.just_pressed(Action::MenuUp) {
+        menu.move_selection(-1);
+    } else if controls.just_pressed(Action::MenuDown) {
+        menu.move_selection(1);
+    } else if let Some(step) = step {
+        activated.write(MenuActivated {
+            action: menu.selected_action(),
+            step,
+        });
+    } else if controls.just_pressed(Action::Back) {
+        activated.write(MenuActivated {
+            action: MenuAction::Back,
+            step: 0,
+        });
+    }
+}
+
+/// Everything a menu item can show the value of
+#[derive(SystemParam)]
+pub struct MenuValues<'w> {
+    settings: Res<'w, Settings>,
+    difficulty: Res<'w, Difficulty>,
+    run_mode: Res<'w, RunMode>,
+    localization: Res<'w, Localization>,
+}
+
+impl MenuValues<'_> {
+    fn is_changed(&self) -> bool {
+        self.settings.is_changed()
+            || self.difficulty.is_changed()
+            || self.run_mode.is_changed()
+            || self.localization.is_changed()
+    }
+
+    /// Current value of a setting, `None` for items that only do something
+    fn value(&self, action: MenuAction) -> Option<String> {
+        let settings = &self.settings;
+        let loc = &self.localization;
+        Some(match action {
+            MenuAction::Mode => match *self.run_mode {
+                RunMode::Standard => loc.text("mode.standard"),
+                RunMode::Daily(_) => loc.text("mode.daily"),
+            },
+            MenuAction::Difficulty => loc.text(self.difficulty.key()),
+            MenuAction::MasterVolume => loc.percent(settings.master_volume),
+            MenuAction::MusicVolume => loc.percent(settings.music_volume),
+            MenuAction::SfxVolume => loc.percent(settings.sfx_volume),
+            MenuAction::ScreenShake => loc.percent(settings.screen_shake),
+            MenuAction::StarfieldDensity => loc.percent(settings.starfield_density),
+            MenuAction::GamepadDeadzone => loc.percent(settings.gamepad_deadzone),
+            MenuAction::DisplayMode => loc.text(settings.display_mode.key()),
+            MenuAction::Autofire => loc.on_off(settings.autofire),
+            // Every language is named in itself, so it can be found again
+            MenuAction::Language => Language::from_code(&settings.language).map_or_else(
+                || settings.language.clone(),
+                |language| language.name.to_string(),
+            ),
+            _ => return None,
+        })
+    }
+}
+
+/// Label every menu item with its current value, highlighting the selected one
+pub fn update_menu_display(
+    values: MenuValues,
+    menus: Query<(Ref<Menu>, &Children)>,
+    mut items: Query<(&MenuItem, &mut Text, &mut TextColor)>,
+) {
+    for (menu, children) in menus.iter() {
+        if !menu.is_changed() && !values.is_changed() {
+            continue;
+        }
+
+        let mut iter = items.iter_many_mut(children);
+        while let Some((item, mut text, mut color)) = iter.fetch_next() {
+            let action = menu.items[item.0];
+            let label = values.localization.text(action.key());
+            let label = match values.value(action) {
+                Some(value) => values
+                    .localization
+                    .format("menu.item", &[("label", &label), ("value", &value)]),
+                None => label,
+            };
+            if item.0 == menu.selected {
+                **text = format!("> {label} <");
+                color.0 = Color::srgb(1.0, 0.84, 0.0);
+            } else {
+                **text = label;
+                color.0 = Color::WHITE;
+            }
+        }
+    }
+}
@@ -1,21 +1,63 @@
+pub mod achievements;
+pub mod audio;
 pub mod background;
-pub mod bullet;
 pub mod collision;
+pub mod config;
+pub mod daily;
 pub mod enemy;
 pub mod explosion;
+pub mod initials;
+pub mod input;
+pub mod lifetime;
+pub mod locale;
+pub mod menu;
+pub mod pause;
+pub mod persistence;
+pub mod physics;
 pub mod player;
+pub mod pool;
+pub mod rebinding;
+pub mod scoring;
 pub mod screens;
+pub mod settings;
 pub mod setup;
+pub mod shake;
+pub mod snapshot;
+pub mod stats;
+pub mod touch;
 pub mod ui;
 pub mod victory;
+pub mod waves;
+pub mod window;
 
+pub use achievements::*;
+pub use audio::*;
 pub use background::*;
-pub use bullet::*;
 pub use collision::*;
+pub use config::*;
+pub use daily::*;
 pub use enemy::*;
 pub use explosion::*;
+pub use initials::*;
+pub use input::*;
+pub use lifetime::*;
+pub use locale::*;
+pub use menu::*;
+pub use pause::*;
+pub use persistence::*;
+pub use physics::*;
 pub use player::*;
+pub use pool::*;
+pub use rebinding::*;
+pub use scoring::*;
 pub use screens::*;
+pub use settings::*;
 pub use setup::*;
+pub use shake::*;
+pub use snapshot::*;
+pub use stats::*;
+pub use touch::*;
 pub use ui::*;
 pub use victory::*;
+pub use waves::*;
+pub use window::*;
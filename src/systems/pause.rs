@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::systems::input::PlayerControls;
+use crate::systems::menu::spawn_menu;
+
+/// Pause the run on the Pause action, and resume it on the same action
+pub fn toggle_pause(
+    controls: PlayerControls,
+    run_phase: Res<State<RunPhase>>,
+    mut next_run_phase: ResMut<NextState<RunPhase>>,
+) {
+    if controls.just_pressed(Action::Pause) {
+        next_run_phase.set(match run_phase.get() {
+            RunPhase::Running => RunPhase::Paused,
+            RunPhase::Paused => RunPhase::Running,
+        });
+    }
+}
+
+/// Setup the pause menu over the stopped run
+pub fn setup_pause_menu(mut commands: Commands, localization: Res<Localization>) {
+    // Dim the playfield behind the menu
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        DespawnOnExit(RunPhase::Paused),
+    ));
+
+    // Title text
+    commands.spawn((
+        Text::new(localization.text("pause.title")),
+        TextFont {
+            font_size: 50.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(180.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(RunPhase::Paused),
+    ));
+
+    spawn_menu(
+        &mut commands,
+        Node {
+            top: Val::Px(270.0),
+            ..default()
+        },
+        vec![MenuAction::Resume, MenuAction::Suspend],
+        0,
+        RunPhase::Paused,
+    );
+}
+
+/// Resume the run from the pause menu; Suspend is handled by `suspend_run`
+pub fn pause_menu_input(
+    mut activated: MessageReader<MenuActivated>,
+    mut next_run_phase: ResMut<NextState<RunPhase>>,
+) {
+    for MenuActivated { action, step } in activated.read() {
+        if *step != 0 {
+            continue;
+        }
+        if matches!(action, MenuAction::Resume | MenuAction::Back) {
+            next_run_phase.set(RunPhase::Running);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::achievements::*;
+use crate::config::*;
+use crate::daily::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::storage::*;
+
+/// Load the saved high score table
+pub fn load_high_scores(
+    storage: Res<Storage>,
+    mut high_scores: ResMut<HighScores>,
+    config: Res<GameConfig>,
+) {
+    *high_scores = storage.load();
+    high_scores.set_capacity(config.high_scores.table_size);
+}
+
+/// Load the daily challenge table
+pub fn load_daily_scores(storage: Res<Storage>, mut daily_scores: ResMut<DailyScores>) {
+    *daily_scores = storage.load();
+}
+
+/// Load the saved settings
+pub fn load_settings(storage: Res<Storage>, mut settings: ResMut<Settings>) {
+    *settings = storage.load();
+}
+
+/// Save the settings after they change
+pub fn save_settings(storage: Res<Storage>, settings: Res<Settings>) {
+    // The settings were just loaded, not changed
+    if settings.is_added() {
+        return;
+    }
+    storage.save(&*settings);
+}
+
+/// Load the unlocked achievements
+pub fn load_achievements(storage: Res<Storage>, mut unlocked: ResMut<UnlockedAchievements>) {
+    *unlocked = storage.load();
+}
+
+/// Load the saved lifetime statistics
+pub fn load_lifetime_stats(storage: Res<Storage>, mut lifetime_stats: ResMut<LifetimeStats>) {
+    *lifetime_stats = storage.load();
+}
+
+/// Save the high score table
+pub fn save_high_scores(storage: Res<Storage>, high_scores: Res<HighScores>) {
+    storage.save(&*high_scores);
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+
+/// Components read and written by `integrate_motion`
+type MotionComponents<'a> = (
+    &'a mut Transform,
+    Option<&'a mut Velocity>,
+    Option<&'a Acceleration>,
+    Option<&'a Drag>,
+    Option<&'a AngularVelocity>,
+);
+
+/// Entities that translate, rotate, or both
+type Moving = Or<(With<Velocity>, With<AngularVelocity>)>;
+
+/// Integrate velocity, acceleration, drag and angular velocity for every moving entity
+pub fn integrate_motion(mut query: Query<MotionComponents, Moving>, time: Res<Time>) {
+    let dt = time.delta_secs();
+
+    for (mut transform, velocity, acceleration, drag, angular_velocity) in query.iter_mut() {
+        if let Some(mut velocity) = velocity {
+            // Semi-implicit Euler: update velocity first, then move with the new velocity
+            if let Some(acceleration) = acceleration {
+                velocity.x += acceleration.x * dt;
+                velocity.y += acceleration.y * dt;
+            }
+            if let Some(Drag(drag)) = drag {
+                let damping = (-drag * dt).exp();
+                velocity.x *= damping;
+                velocity.y *= damping;
+            }
+
+            transform.translation.x += velocity.x * dt;
+            transform.translation.y += velocity.y * dt;
+        }
+
+        if let Some(AngularVelocity(angular_velocity)) = angular_velocity {
+            transform.rotate_z(angular_velocity * dt);
+        }
+    }
+}
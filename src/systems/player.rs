@@ -1,68 +1,70 @@
+use bevy::ecs::entity_disabling::Disabled;
 use bevy::prelude::*;
 
 use crate::components::*;
+use crate::config::*;
 use crate::constants::*;
+use crate::messages::*;
 use crate::resources::*;
 
 /// Handle player movement
 pub fn player_movement(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    tick_input: Res<TickInput>,
     mut query: Query<&mut Transform, With<Player>>,
     time: Res<Time>,
-    window_dims: Res<WindowDimensions>,
+    config: Res<GameConfig>,
 ) {
     if let Ok(mut transform) = query.single_mut() {
-        let mut direction = 0.0;
-
-        if keyboard_input.pressed(KeyCode::ArrowLeft) || keyboard_input.pressed(KeyCode::KeyA) {
-            direction -= 1.0;
-        }
-        if keyboard_input.pressed(KeyCode::ArrowRight) || keyboard_input.pressed(KeyCode::KeyD) {
-            direction += 1.0;
-        }
-
-        let new_x = transform.translation.x + direction * PLAYER_SPEED * time.delta_secs();
+        let step = config.player.speed * time.delta_secs();
+        let new_x = transform.translation.x + tick_input.steering() * step;
         let half_player = PLAYER_SIZE / 2.0;
         transform.translation.x = new_x.clamp(
-            -window_dims.width / 2.0 + half_player,
-            window_dims.width / 2.0 - half_player,
+            -PLAYFIELD_WIDTH / 2.0 + half_player,
+            PLAYFIELD_WIDTH / 2.0 - half_player,
         );
     }
 }
 
-/// Handle player shooting
+/// Handle player shooting: a shot on each press, and with autofire on, repeated
+/// shots while Fire is held
+#[allow(clippy::too_many_arguments)]
 pub fn player_shoot(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    tick_input: Res<TickInput>,
     mut commands: Commands,
     query: Query<&Transform, With<Player>>,
-    textures: Res<GameTextures>,
-    audio: Res<GameAudio>,
+    mut pools: ResMut<EntityPools>,
+    mut game_state: ResMut<GameState>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut shot_fired: MessageWriter<ShotFired>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space)
+    game_state.player_fire_timer += time.delta_secs();
+    let autofire =
+        tick_input.autofire && game_state.player_fire_timer >= config.player.autofire_interval;
+
+    // No shot is fired while every pooled bullet is in flight
+    if (tick_input.fire || autofire)
         && let Ok(transform) = query.single()
+        && let Some(bullet) = pools.bullets.acquire()
     {
-        commands.spawn((
-            Sprite {
-                image: textures.bullet.clone(),
-                custom_size: Some(Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0)),
-                ..default()
-            },
-            Transform::from_xyz(
-                transform.translation.x,
-                transform.translation.y + PLAYER_SIZE / 2.0,
-                0.0,
-            ),
-            Bullet,
-            Velocity {
-                x: 0.0,
-                y: BULLET_SPEED,
-            },
-        ));
+        commands
+            .entity(bullet)
+            .insert((
+                Transform::from_xyz(
+                    transform.translation.x,
+                    transform.translation.y + PLAYER_SIZE / 2.0,
+                    0.0,
+                ),
+                Velocity {
+                    x: 0.0,
+                    y: config.bullet.speed,
+                },
+            ))
+            .remove::<Disabled>();
+        game_state.player_fire_timer = 0.0;
 
-        // Play shoot sound
-        commands.spawn((
-            AudioPlayer::new(audio.player_shoot.clone()),
-            PlaybackSettings::DESPAWN,
-        ));
+        shot_fired.write(ShotFired {
+            shooter: Shooter::Player,
+        });
     }
 }
@@ -0,0 +1,129 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::config::*;
+use crate::constants::*;
+use crate::resources::*;
+
+/// Recycle entities released this frame and grow the pools to their configured sizes
+pub fn maintain_entity_pools(
+    mut commands: Commands,
+    mut pools: ResMut<EntityPools>,
+    config: Res<GameConfig>,
+    textures: Res<GameTextures>,
+) {
+    let pools = &mut *pools;
+    pools.stars.recycle_released();
+    pools.explosion_particles.recycle_released();
+    pools.bullets.recycle_released();
+    pools.enemy_bullets.recycle_released();
+
+    // Pools only grow, so a smaller size in a reloaded config takes effect on restart
+    while pools.stars.size() < config.pools.stars {
+        let entity = commands
+            .spawn((
+                Sprite::default(),
+                Transform::default(),
+                Star {
+                    distance_from_center: 0.0,
+                },
+                Velocity { x: 0.0, y: 0.0 },
+                OffscreenDespawn { margin: 10.0 },
+                Pooled(PoolKind::Star),
+                Disabled,
+            ))
+            .id();
+        pools.stars.add(entity);
+    }
+
+    while pools.explosion_particles.size() < config.pools.explosion_particles {
+        let entity = commands
+            .spawn((
+                Sprite::default(),
+                Transform::default(),
+                ExplosionParticle,
+                Lifetime::new(0.0),
+                Velocity { x: 0.0, y: 0.0 },
+                Acceleration { x: 0.0, y: 0.0 },
+                Drag(0.0),
+                Pooled(PoolKind::ExplosionParticle),
+                Disabled,
+            ))
+            .id();
+        pools.explosion_particles.add(entity);
+    }
+
+    while pools.bullets.size() < config.pools.bullets {
+        let entity = commands
+            .spawn((
+                Sprite {
+                    image: textures.bullet.clone(),
+                    custom_size: Some(Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0)),
+                    ..default()
+                },
+                Transform::default(),
+                Bullet,
+                Hitbox::new(
+                    Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0),
+                    CollisionLayer::PLAYER_SHOT,
+                    CollisionLayer::ENEMY | CollisionLayer::SHIELD,
+                ),
+                Velocity { x: 0.0, y: 0.0 },
+                OffscreenDespawn { margin: 10.0 },
+                Pooled(PoolKind::Bullet),
+                Disabled,
+            ))
+            .id();
+        pools.bullets.add(entity);
+    }
+
+    while pools.enemy_bullets.size() < config.pools.enemy_bullets {
+        let entity = commands
+            .spawn((
+                Sprite {
+                    image: textures.enemy_bullet.clone(),
+                    custom_size: Some(Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0)),
+                    ..default()
+                },
+                Transform::default(),
+                EnemyBullet,
+                Hitbox::new(
+                    Vec2::new(BULLET_SIZE, BULLET_SIZE * 2.0),
+                    CollisionLayer::ENEMY_SHOT,
+                    CollisionLayer::PLAYER | CollisionLayer::SHIELD,
+                ),
+                Velocity { x: 0.0, y: 0.0 },
+                OffscreenDespawn { margin: 10.0 },
+                Pooled(PoolKind::EnemyBullet),
+                Disabled,
+            ))
+            .id();
+        pools.enemy_bullets.add(entity);
+    }
+}
+
+/// Despawn an entity, or return it to its pool if it is pooled
+pub fn despawn_or_release(
+    commands: &mut Commands,
+    pools: &mut EntityPools,
+    entity: Entity,
+    pooled: Option<&Pooled>,
+) {
+    match pooled {
+        Some(Pooled(kind)) => pools.get_mut(*kind).release(commands, entity),
+        None => commands.entity(entity).despawn(),
+    }
+}
+
+/// Return every active pooled entity to its pool. Pooled entities outlive game
+/// phases, so they are released rather than despawned when gameplay ends.
+pub fn release_pooled_entities(
+    mut commands: Commands,
+    query: Query<(Entity, &Pooled)>,
+    mut pools: ResMut<EntityPools>,
+) {
+    for (entity, pooled) in query.iter() {
+        pools.get_mut(pooled.0).release(&mut commands, entity);
+    }
+}
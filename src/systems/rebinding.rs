@@ -0,0 +1,365 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::systems::input::PlayerControls;
+use crate::systems::touch::spawn_tap_row;
+
+/// How far a stick has to be pushed to be captured as a binding
+const STICK_CAPTURE_THRESHOLD: f32 = 0.5;
+
+const ALT_KEYS: [KeyCode; 2] = [KeyCode::AltLeft, KeyCode::AltRight];
+
+/// Setup the controls screen
+pub fn setup_controls_screen(
+    mut commands: Commands,
+    mut menu: ResMut<RebindingMenu>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+) {
+    *menu = RebindingMenu::default();
+
+    // Title text
+    commands.spawn((
+        Text::new(localization.text("controls.title")),
+        TextFont {
+            font_size: 44.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.0, 1.0, 0.5)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Controls),
+    ));
+
+    // Actions and their bindings, filled in by update_bindings_display
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(105.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        BindingsText,
+        DespawnOnExit(GamePhase::Controls),
+    ));
+
+    // Status text, filled in by update_bindings_display
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.84, 0.0)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(385.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        RebindStatusText,
+        DespawnOnExit(GamePhase::Controls),
+    ));
+
+    // Instructions text
+    commands.spawn((
+        Text::new(settings.bindings.hint("controls.hint", &localization)),
+        BindingHint("controls.hint"),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(425.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Controls),
+    ));
+
+    // Changing the autofire setting and the way back without a keyboard
+    spawn_tap_row(
+        &mut commands,
+        530.0,
+        &[
+            (
+                localization.text("menu.up"),
+                TapTarget::Action(Action::MenuUp),
+            ),
+            (
+                localization.text("menu.down"),
+                TapTarget::Action(Action::MenuDown),
+            ),
+            (
+                localization.text("action.confirm"),
+                TapTarget::Action(Action::Confirm),
+            ),
+            (
+                localization.text("menu.back"),
+                TapTarget::Action(Action::Back),
+            ),
+        ],
+        GamePhase::Controls,
+    );
+}
+
+/// The first input pressed this frame on any device, for binding. F11 and
+/// Alt+Enter toggle fullscreen instead, so Alt is only bound when it is
+/// released without having been held for Alt+Enter.
+fn captured_binding(
+    keyboard: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepad: Option<&Gamepad>,
+    alt_chord: bool,
+) -> Option<Binding> {
+    let alt = keyboard.any_pressed(ALT_KEYS);
+    keyboard
+        .get_just_pressed()
+        .find(|key| {
+            !ALT_KEYS.contains(key) && **key != KeyCode::F11 && !(alt && **key == KeyCode::Enter)
+        })
+        .or_else(|| {
+            keyboard
+                .get_just_released()
+                .find(|key| ALT_KEYS.contains(key) && !alt_chord)
+        })
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Mouse(*button))
+        })
+        .or_else(|| {
+            let gamepad = gamepad?;
+            gamepad
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(*button))
+                .or_else(|| {
+                    bindable_stick_axes().find_map(|axis| {
+                        let value = gamepad.get(axis)?;
+                        (value.abs() > STICK_CAPTURE_THRESHOLD).then_some(Binding::Stick {
+                            axis,
+                            positive: value > 0.0,
+                        })
+                    })
+                })
+        })
+}
+
+/// Navigate the controls screen and add, remove or reset bindings. Every
+/// action keeps at least one input, so the screen can always be navigated.
+#[allow(clippy::too_many_arguments)]
+pub fn controls_screen_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pointer: Res<PointerInput>,
+    mut input: ParamSet<(PlayerControls, ResMut<Settings>)>,
+    mut menu: ResMut<RebindingMenu>,
+    localization: Res<Localization>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+    mut alt_chord: Local<bool>,
+) {
+    // Alt+Enter toggles fullscreen instead; remember it until Alt is pressed again
+    if keyboard.any_just_pressed(ALT_KEYS) {
+        *alt_chord = false;
+    }
+    if keyboard.any_pressed(ALT_KEYS) && keyboard.just_pressed(KeyCode::Enter) {
+        *alt_chord = true;
+    }
+
+    let row = menu.selected_row();
+
+    if menu.capturing {
+        let ControlsRow::Action(action) = row else {
+            menu.capturing = false;
+            return;
+        };
+        if keyboard.just_pressed(KeyCode::Escape)
+            || pointer.just_tapped(TapTarget::Action(Action::Back))
+        {
+            menu.capturing = false;
+            menu.status = localization.text("controls.cancelled");
+            return;
+        }
+        let controls = input.p0();
+        let Some(binding) = captured_binding(&keyboard, &mouse, controls.gamepad(), *alt_chord)
+        else {
+            return;
+        };
+
+        menu.capturing = false;
+        let action_name = localization.text(action.key());
+        let label = binding.label(&localization);
+        let mut settings = input.p1();
+        let mut bindings = settings.bindings.clone();
+        menu.status = match bindings.bind(action, binding) {
+            Ok(()) => {
+                settings.bindings = bindings;
+                localization.format(
+                    "controls.added",
+                    &[("input", &label), ("action", &action_name)],
+                )
+            }
+            Err(BindError::AlreadyBound) => localization.format(
+                "controls.already_bound",
+                &[("input", &label), ("action", &action_name)],
+            ),
+            Err(BindError::Conflict(other)) => localization.format(
+                "controls.conflict",
+                &[
+                    ("input", &label),
+                    ("other", &localization.text(other.key())),
+                ],
+            ),
+            Err(BindError::Unbindable) => {
+                localization.format("controls.unbindable", &[("input", &label)])
+            }
+        };
+        return;
+    }
+
+    let controls = input.p0();
+    let [up, down, left, right, confirm, back] = [
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::Confirm,
+        Action::Back,
+    ]
+    .map(|action| controls.just_pressed(action));
+    let mut settings = input.p1();
+
+    if up {
+        menu.move_selection(-1);
+    } else if down {
+        menu.move_selection(1);
+    } else if left || right || confirm {
+        match row {
+            // Left removes the last input of an action, Right or Confirm adds one
+            ControlsRow::Action(action) if left => {
+                let action_name = localization.text(action.key());
+                let mut bindings = settings.bindings.clone();
+                menu.status = match bindings.unbind_last(action) {
+                    Some(removed) => {
+                        settings.bindings = bindings;
+                        localization.format(
+                            "controls.removed",
+                            &[
+                                ("input", &removed.label(&localization)),
+                                ("action", &action_name),
+                            ],
+                        )
+                    }
+                    None => localization.format("controls.last_input", &[("action", &action_name)]),
+                };
+            }
+            ControlsRow::Action(action) => {
+                menu.capturing = true;
+                // Escape always cancels, whatever it is bound to
+                menu.status = localization.format(
+                    "controls.capture",
+                    &[
+                        ("action", &localization.text(action.key())),
+                        (
+                            "cancel",
+                            &Binding::Key(KeyCode::Escape).label(&localization),
+                        ),
+                    ],
+                );
+            }
+            ControlsRow::Autofire => {
+                settings.autofire = !settings.autofire;
+                menu.status = localization.format(
+                    "controls.autofire",
+                    &[("state", &localization.on_off(settings.autofire))],
+                );
+            }
+            ControlsRow::Reset if confirm => {
+                settings.bindings = ActionBindings::default();
+                menu.status = localization.text("controls.reset");
+            }
+            ControlsRow::Reset => {}
+        }
+    } else if back {
+        next_state.set(GamePhase::StartScreen);
+    }
+}
+
+/// Show every action with its bindings, the autofire setting and the reset row,
+/// marking the selected one
+pub fn update_bindings_display(
+    menu: Res<RebindingMenu>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut list_query: Query<&mut Text, (With<BindingsText>, Without<RebindStatusText>)>,
+    mut status_query: Query<&mut Text, (With<RebindStatusText>, Without<BindingsText>)>,
+) {
+    if !menu.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let mut lines: Vec<String> = Action::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let bindings = if menu.capturing && i == menu.selected {
+                "...".to_string()
+            } else {
+                settings.bindings.describe(*action, &localization)
+            };
+            let marker = if i == menu.selected { ">" } else { " " };
+            let line = localization.format(
+                "controls.binding",
+                &[
+                    ("action", &localization.text(action.key())),
+                    ("inputs", &bindings),
+                ],
+            );
+            format!("{marker} {line}")
+        })
+        .collect();
+    let autofire = localization.format(
+        "controls.binding",
+        &[
+            ("action", &localization.text("menu.autofire")),
+            ("inputs", &localization.on_off(settings.autofire)),
+        ],
+    );
+    let marker = |row| if menu.selected_row() == row { ">" } else { " " };
+    lines.push(format!("{} {autofire}", marker(ControlsRow::Autofire)));
+    lines.push(format!(
+        "{} {}",
+        marker(ControlsRow::Reset),
+        localization.text("controls.reset_row")
+    ));
+
+    for mut text in list_query.iter_mut() {
+        **text = lines.join("\n");
+    }
+    for mut text in status_query.iter_mut() {
+        **text = menu.status.clone();
+    }
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+use crate::messages::*;
+use crate::resources::*;
+
+/// Award points for destroyed enemies
+pub fn award_kill_points(
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut game_state: ResMut<GameState>,
+) {
+    for kill in enemy_killed.read() {
+        game_state.score += kill.points;
+    }
+}
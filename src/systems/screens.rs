@@ -1,13 +1,36 @@
+use std::ops::Range;
+
 use bevy::prelude::*;
+use chrono::{Local, NaiveDate};
 
 use crate::components::*;
+use crate::config::GameConfig;
+use crate::constants::*;
+use crate::daily::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::messages::*;
 use crate::resources::*;
+use crate::settings::*;
+use crate::snapshot::*;
+use crate::storage::*;
+use crate::systems::menu::spawn_menu;
+use crate::systems::stats::format_duration;
 
 /// Setup start screen
-pub fn setup_start_screen(mut commands: Commands) {
+pub fn setup_start_screen(
+    mut commands: Commands,
+    suspended: Res<SuspendedRun>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut run_mode: ResMut<RunMode>,
+) {
+    // Runs are standard unless the daily challenge is picked again
+    *run_mode = RunMode::Standard;
+
     // Title text
     commands.spawn((
-        Text::new("VIBE INVADERS"),
+        Text::new(localization.text("start.title")),
         TextFont {
             font_size: 60.0,
             ..default()
@@ -16,90 +39,264 @@ pub fn setup_start_screen(mut commands: Commands) {
         TextLayout::new_with_justify(Justify::Center),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(150.0),
+            top: Val::Px(60.0),
             width: Val::Percent(100.0),
             ..default()
         },
-        StartScreenUI,
+        DespawnOnExit(GamePhase::StartScreen),
     ));
 
+    // Main menu, starting on Play; Continue is listed first when a suspended
+    // run can be resumed
+    let mut items = Vec::new();
+    if suspended.snapshot.is_some() {
+        items.push(MenuAction::Continue);
+    }
+    let play = items.len();
+    items.extend([
+        MenuAction::Play,
+        MenuAction::Mode,
+        MenuAction::Difficulty,
+        MenuAction::Settings,
+        MenuAction::HighScores,
+        MenuAction::Stats,
+        MenuAction::Achievements,
+        MenuAction::Controls,
+    ]);
+    // Browsers close tabs themselves
+    #[cfg(not(target_arch = "wasm32"))]
+    items.push(MenuAction::Quit);
+    spawn_menu(
+        &mut commands,
+        Node {
+            top: Val::Px(150.0),
+            ..default()
+        },
+        items,
+        play,
+        GamePhase::StartScreen,
+    );
+
     // Instructions text
     commands.spawn((
-        Text::new("Press SPACE to Start"),
+        Text::new(settings.bindings.hint("start.hint", &localization)),
+        BindingHint("start.hint"),
         TextFont {
-            font_size: 30.0,
+            font_size: 16.0,
             ..default()
         },
-        TextColor(Color::WHITE),
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
         TextLayout::new_with_justify(Justify::Center),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(300.0),
+            top: Val::Px(475.0),
             width: Val::Percent(100.0),
             ..default()
         },
-        StartScreenUI,
+        DespawnOnExit(GamePhase::StartScreen),
     ));
 
-    // Controls text
+    // Controls text, from the current bindings
+    let controls: Vec<String> = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Pause,
+    ]
+    .into_iter()
+    .map(|action| {
+        localization.format(
+            "controls.binding",
+            &[
+                ("action", &localization.text(action.key())),
+                ("inputs", &settings.bindings.describe(action, &localization)),
+            ],
+        )
+    })
+    .collect();
     commands.spawn((
-        Text::new("Controls:\nArrow Keys or A/D - Move\nSPACE - Shoot"),
+        Text::new(controls.join("   ")),
         TextFont {
-            font_size: 20.0,
+            font_size: 16.0,
             ..default()
         },
         TextColor(Color::srgb(0.7, 0.7, 0.7)),
         TextLayout::new_with_justify(Justify::Center),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(400.0),
+            top: Val::Px(530.0),
             width: Val::Percent(100.0),
             ..default()
         },
-        StartScreenUI,
+        DespawnOnExit(GamePhase::StartScreen),
     ));
 }
 
-/// Handle start screen input
-pub fn start_screen_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut next_state: ResMut<NextState<GamePhase>>,
+/// Start today's daily challenge, unless it was already played. The attempt is
+/// used up as soon as the challenge starts, and every player gets the same
+/// difficulty.
+fn start_daily_challenge(
+    date: NaiveDate,
+    daily_scores: &mut DailyScores,
+    storage: &Storage,
+    run_mode: &mut RunMode,
+    difficulty: &mut Difficulty,
+    next_state: &mut NextState<GamePhase>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    if daily_scores.start(date) {
+        storage.save(&*daily_scores);
+        *run_mode = RunMode::Daily(date);
+        *difficulty = Difficulty::Normal;
         next_state.set(GamePhase::Playing);
     }
 }
 
-/// Cleanup start screen
-pub fn cleanup_start_screen(mut commands: Commands, query: Query<Entity, With<StartScreenUI>>) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn();
+/// Handle the items of the main menu
+#[allow(clippy::too_many_arguments)]
+pub fn start_menu_input(
+    mut activated: MessageReader<MenuActivated>,
+    mut suspended: ResMut<SuspendedRun>,
+    mut run_mode: ResMut<RunMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut daily_scores: ResMut<DailyScores>,
+    storage: Res<Storage>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    for MenuActivated { action, step } in activated.read() {
+        match (action, *step) {
+            (MenuAction::Mode, _) => match *run_mode {
+                RunMode::Standard => {
+                    let today = Local::now().date_naive();
+                    if !daily_scores.attempted(today) {
+                        *run_mode = RunMode::Daily(today);
+                        *difficulty = Difficulty::Normal;
+                    }
+                }
+                RunMode::Daily(_) => *run_mode = RunMode::Standard,
+            },
+            // The daily challenge is always played on Normal
+            (MenuAction::Difficulty, step) if *run_mode == RunMode::Standard => {
+                *difficulty = difficulty.cycle(if step == 0 { 1 } else { step });
+            }
+            (MenuAction::Continue, 0) if suspended.snapshot.is_some() => {
+                suspended.resuming = true;
+                next_state.set(GamePhase::Playing);
+            }
+            (MenuAction::Play, 0) => match *run_mode {
+                RunMode::Standard => next_state.set(GamePhase::Playing),
+                RunMode::Daily(date) => start_daily_challenge(
+                    date,
+                    &mut daily_scores,
+                    &storage,
+                    &mut run_mode,
+                    &mut difficulty,
+                    &mut next_state,
+                ),
+            },
+            (MenuAction::Settings, 0) => next_state.set(GamePhase::Settings),
+            (MenuAction::HighScores, 0) => next_state.set(GamePhase::HighScores),
+            (MenuAction::Stats, 0) => next_state.set(GamePhase::Stats),
+            (MenuAction::Achievements, 0) => next_state.set(GamePhase::Achievements),
+            (MenuAction::Controls, 0) => next_state.set(GamePhase::Controls),
+            (MenuAction::Quit, 0) => {
+                app_exit.write(AppExit::Success);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Setup the high scores screen
+pub fn setup_high_scores_screen(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    localization: Res<Localization>,
+) {
+    spawn_high_score_table(
+        &mut commands,
+        &high_scores,
+        &localization,
+        60.0..505.0,
+        GamePhase::HighScores,
+    );
+    spawn_menu(
+        &mut commands,
+        Node {
+            bottom: Val::Px(50.0),
+            ..default()
+        },
+        vec![MenuAction::Back],
+        0,
+        GamePhase::HighScores,
+    );
+}
+
+/// Return from the high scores screen
+pub fn high_scores_screen_input(
+    mut activated: MessageReader<MenuActivated>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    for MenuActivated { action, step } in activated.read() {
+        if *action == MenuAction::Back && *step == 0 {
+            next_state.set(GamePhase::StartScreen);
+        }
+    }
+}
+
+/// Whether the run goes on: once it has ended, the remaining ticks of that
+/// frame play nothing more
+pub fn run_in_progress(next_phase: Res<NextState<GamePhase>>) -> bool {
+    matches!(*next_phase, NextState::Unchanged)
+}
+
+/// End the run: go to initials entry when the score makes the high score table,
+/// straight to the game over screen otherwise. Daily challenges have their own
+/// table and never enter the high scores.
+pub fn finish_run(
+    mut run_ended: MessageReader<RunEnded>,
+    game_state: Res<GameState>,
+    run_mode: Res<RunMode>,
+    mut high_scores: ResMut<HighScores>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    if run_ended.read().count() == 0 {
+        return;
+    }
+
+    high_scores.clear_current();
+    if *run_mode == RunMode::Standard && high_scores.qualifies(game_state.score) {
+        next_state.set(GamePhase::NameEntry);
+    } else {
+        next_state.set(GamePhase::GameOver);
     }
 }
 
 /// Setup game over screen
+#[allow(clippy::too_many_arguments)]
 pub fn setup_game_over_screen(
     mut commands: Commands,
     game_state: Res<GameState>,
-    mut high_scores: ResMut<HighScores>,
+    run_stats: Res<RunStats>,
+    high_scores: Res<HighScores>,
+    run_mode: Res<RunMode>,
+    daily_scores: Res<DailyScores>,
+    input_log: Res<InputLog>,
+    localization: Res<Localization>,
     mut game_over_timer: ResMut<GameOverTimer>,
-    window_dims: Res<WindowDimensions>,
 ) {
     // Reset timer
     game_over_timer.reset();
 
-    // Add current score to high scores
-    high_scores.add_score(game_state.score);
-
     let (title, title_color) = if game_state.victory {
-        ("VICTORY!", Color::srgb(0.0, 1.0, 0.0))
+        ("game_over.victory", Color::srgb(0.0, 1.0, 0.0))
     } else {
-        ("GAME OVER", Color::srgb(1.0, 0.0, 0.0))
+        ("game_over.title", Color::srgb(1.0, 0.0, 0.0))
     };
 
     // Game over or victory text
     commands.spawn((
-        Text::new(title),
+        Text::new(localization.text(title)),
         TextFont {
             font_size: 60.0,
             ..default()
@@ -112,12 +309,15 @@ pub fn setup_game_over_screen(
             width: Val::Percent(100.0),
             ..default()
         },
-        GameOverUI,
+        DespawnOnExit(GamePhase::GameOver),
     ));
 
     // Final score text
     commands.spawn((
-        Text::new(format!("Final Score: {}", game_state.score)),
+        Text::new(localization.format(
+            "game_over.final_score",
+            &[("score", &localization.number(game_state.score.into()))],
+        )),
         TextFont {
             font_size: 40.0,
             ..default()
@@ -130,12 +330,114 @@ pub fn setup_game_over_screen(
             width: Val::Percent(100.0),
             ..default()
         },
-        GameOverUI,
+        DespawnOnExit(GamePhase::GameOver),
     ));
 
+    // Run statistics text
+    let stats = &run_stats.stats;
+    let cause = match run_stats.outcome {
+        Some(RunOutcome::Victory) => localization.text("game_over.cause.victory"),
+        Some(RunOutcome::Shot) => localization.text("game_over.cause.shot"),
+        Some(RunOutcome::Invaded) => localization.text("game_over.cause.invaded"),
+        None => String::new(),
+    };
+    let number = |value: u32| localization.number(value.into());
+    commands.spawn((
+        Text::new(localization.format(
+            "game_over.summary",
+            &[
+                ("cause", &cause),
+                ("time", &format_duration(stats.time_survived)),
+                (
+                    "waves",
+                    &localization.plural(
+                        "game_over.waves_cleared",
+                        stats.waves_cleared.into(),
+                        &[],
+                    ),
+                ),
+                ("shots", &number(stats.shots_fired)),
+                ("hits", &number(stats.hits)),
+                ("accuracy", &localization.percent(stats.accuracy())),
+                ("saucers", &number(stats.saucers_hit)),
+                ("type1", &number(stats.kills.type1)),
+                ("type2", &number(stats.kills.type2)),
+                ("type3", &number(stats.kills.type3)),
+            ],
+        )),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(180.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::GameOver),
+    ));
+
+    if let RunMode::Daily(date) = *run_mode {
+        spawn_daily_table(&mut commands, &daily_scores, &localization);
+
+        // Code of this run, to share the result
+        let code = DailyCode::new(date, game_state.score, &input_log);
+        commands.spawn((
+            Text::new(
+                localization.format("game_over.daily_code", &[("date", &date), ("code", &code)]),
+            ),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.0, 1.0, 0.5)),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(475.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            DespawnOnExit(GamePhase::GameOver),
+        ));
+    } else {
+        spawn_high_score_table(
+            &mut commands,
+            &high_scores,
+            &localization,
+            255.0..490.0,
+            GamePhase::GameOver,
+        );
+    }
+
+    spawn_menu(
+        &mut commands,
+        Node {
+            bottom: Val::Px(30.0),
+            ..default()
+        },
+        vec![MenuAction::Restart, MenuAction::MainMenu],
+        0,
+        GamePhase::GameOver,
+    );
+}
+
+/// Show the high score table within the vertical span `area`, on the screen of
+/// `phase`. Rows shrink when a large table would not fit otherwise.
+fn spawn_high_score_table(
+    commands: &mut Commands,
+    high_scores: &HighScores,
+    localization: &Localization,
+    area: Range<f32>,
+    phase: GamePhase,
+) {
+    let top = area.start;
     // High scores title
     commands.spawn((
-        Text::new("HIGH SCORES"),
+        Text::new(localization.text("high_scores.title")),
         TextFont {
             font_size: 35.0,
             ..default()
@@ -144,18 +446,40 @@ pub fn setup_game_over_screen(
         TextLayout::new_with_justify(Justify::Center),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(200.0),
+            top: Val::Px(top),
             width: Val::Percent(100.0),
             ..default()
         },
-        GameOverUI,
+        DespawnOnExit(phase),
     ));
 
-    // Display top 5 scores
+    // Display the high score table
     let top_scores = high_scores.get_top_scores();
+    let rows_top = top + 45.0;
+    let row_height =
+        ((area.end - rows_top) / top_scores.len().max(1) as f32).min(HIGH_SCORE_ROW_HEIGHT);
     for (i, score_entry) in top_scores.iter().enumerate() {
-        let y_pos = 250.0 + i as f32 * 40.0;
-        let score_text = format!("{}. {}", i + 1, score_entry.score);
+        let y_pos = rows_top + i as f32 * row_height;
+        let date = score_entry
+            .date
+            .map_or_else(|| "----------".to_string(), |date| date.to_string());
+        let score_text = localization.format(
+            "high_scores.row",
+            &[
+                ("rank", &(i + 1)),
+                ("name", &score_entry.name),
+                (
+                    "score",
+                    &format!("{:>6}", localization.number(score_entry.score.into())),
+                ),
+                ("wave", &format!("{:<2}", score_entry.wave)),
+                (
+                    "difficulty",
+                    &format!("{:<6}", localization.text(score_entry.difficulty.key())),
+                ),
+                ("date", &date),
+            ],
+        );
         let color = if score_entry.is_current {
             Color::srgb(0.0, 1.0, 0.5) // Highlight current score
         } else {
@@ -165,7 +489,7 @@ pub fn setup_game_over_screen(
         commands.spawn((
             Text::new(score_text),
             TextFont {
-                font_size: 28.0,
+                font_size: row_height * 0.75,
                 ..default()
             },
             TextColor(color),
@@ -176,32 +500,77 @@ pub fn setup_game_over_screen(
                 width: Val::Percent(100.0),
                 ..default()
             },
-            GameOverUI,
+            DespawnOnExit(phase),
         ));
     }
+}
 
-    // Restart text
+/// Show the daily challenge table on the game over screen
+fn spawn_daily_table(
+    commands: &mut Commands,
+    daily_scores: &DailyScores,
+    localization: &Localization,
+) {
+    // Daily challenge title
     commands.spawn((
-        Text::new("Press SPACE to Restart"),
+        Text::new(localization.text("daily.title")),
         TextFont {
-            font_size: 25.0,
+            font_size: 35.0,
             ..default()
         },
-        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextColor(Color::srgb(1.0, 0.84, 0.0)),
         TextLayout::new_with_justify(Justify::Center),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(window_dims.height - 80.0),
+            top: Val::Px(255.0),
             width: Val::Percent(100.0),
             ..default()
         },
-        GameOverUI,
+        DespawnOnExit(GamePhase::GameOver),
     ));
+
+    // One row per day, best score first
+    for (i, entry) in daily_scores.scores.iter().enumerate() {
+        let color = if entry.is_current {
+            Color::srgb(0.0, 1.0, 0.5) // Highlight current score
+        } else {
+            Color::WHITE
+        };
+
+        commands.spawn((
+            Text::new(localization.format(
+                "daily.row",
+                &[
+                    ("rank", &(i + 1)),
+                    ("date", &entry.date),
+                    (
+                        "score",
+                        &format!("{:>6}", localization.number(entry.score.into())),
+                    ),
+                    ("wave", &format!("{:<2}", entry.wave)),
+                ],
+            )),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(color),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(300.0 + i as f32 * 32.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            DespawnOnExit(GamePhase::GameOver),
+        ));
+    }
 }
 
-/// Handle game over screen input
+/// Handle the game over menu
 pub fn game_over_screen_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut activated: MessageReader<MenuActivated>,
+    mut run_mode: ResMut<RunMode>,
     mut next_state: ResMut<NextState<GamePhase>>,
     mut game_over_timer: ResMut<GameOverTimer>,
     time: Res<Time>,
@@ -209,63 +578,24 @@ pub fn game_over_screen_input(
     // Update timer
     game_over_timer.elapsed += time.delta_secs();
 
-    // Only allow restart after delay
-    if keyboard_input.just_pressed(KeyCode::Space) && game_over_timer.can_restart() {
-        next_state.set(GamePhase::Playing);
-    }
-}
-
-/// Cleanup game over screen
-pub fn cleanup_game_over_screen(
-    mut commands: Commands,
-    query: Query<Entity, With<GameOverUI>>,
-    mut game_state: ResMut<GameState>,
-) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn();
+    for MenuActivated { action, step } in activated.read() {
+        // Only allow leaving after delay
+        if *step != 0 || !game_over_timer.can_restart() {
+            continue;
+        }
+        match action {
+            // Restarting always plays a standard run; the daily attempt is used up
+            MenuAction::Restart => {
+                *run_mode = RunMode::Standard;
+                next_state.set(GamePhase::Playing);
+            }
+            MenuAction::MainMenu => next_state.set(GamePhase::StartScreen),
+            _ => {}
+        }
     }
-
-    // Reset game state for next game
-    game_state.reset();
 }
 
-/// Cleanup game entities (player, enemies, bullets)
-pub fn cleanup_game_entities(
-    mut commands: Commands,
-    player_query: Query<Entity, With<Player>>,
-    enemy_query: Query<Entity, With<Enemy>>,
-    bullet_query: Query<Entity, With<Bullet>>,
-    enemy_bullet_query: Query<Entity, With<EnemyBullet>>,
-    score_query: Query<Entity, With<Score>>,
-    music_query: Query<Entity, With<BackgroundMusic>>,
-) {
-    // Despawn player
-    for entity in player_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Despawn enemies
-    for entity in enemy_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Despawn bullets
-    for entity in bullet_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Despawn enemy bullets
-    for entity in enemy_bullet_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Despawn score UI
-    for entity in score_query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Despawn background music
-    for entity in music_query.iter() {
-        commands.entity(entity).despawn();
-    }
+/// Reset game state for the next game when leaving the game over screen
+pub fn reset_game_state(mut game_state: ResMut<GameState>, config: Res<GameConfig>) {
+    game_state.reset(config.player.lives);
 }
@@ -0,0 +1,167 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::components::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::systems::menu::spawn_menu;
+
+/// Change of a level setting per step
+const LEVEL_STEP: f32 = 0.1;
+/// Highest stick deadzone; a full deadzone would ignore the stick entirely
+const MAX_GAMEPAD_DEADZONE: f32 = 0.9;
+/// Highest starfield density multiplier
+const MAX_STARFIELD_DENSITY: f32 = 2.0;
+
+/// Apply changed settings to the window and to the music already playing
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut music_query: Query<&mut AudioSink, With<BackgroundMusic>>,
+) {
+    let mode = settings.display_mode.window_mode();
+    if let Ok(mut window) = window_query.single_mut()
+        && window.mode != mode
+    {
+        window.mode = mode;
+    }
+
+    for mut sink in music_query.iter_mut() {
+        sink.set_volume(Volume::Linear(settings.music_level()));
+    }
+}
+
+/// Switch between a window and fullscreen with F11 or Alt+Enter, on any screen
+pub fn toggle_fullscreen(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    let alt = keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    if keyboard.just_pressed(KeyCode::F11) || (alt && keyboard.just_pressed(KeyCode::Enter)) {
+        settings.display_mode = settings.display_mode.toggled();
+    }
+}
+
+/// Setup the settings screen
+pub fn setup_settings_screen(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+) {
+    // Title text, relabelled when the language changes
+    commands.spawn((
+        localization.localized("settings.title"),
+        TextFont {
+            font_size: 44.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.0, 1.0, 0.5)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Settings),
+    ));
+
+    spawn_menu(
+        &mut commands,
+        Node {
+            top: Val::Px(130.0),
+            ..default()
+        },
+        vec![
+            MenuAction::MasterVolume,
+            MenuAction::MusicVolume,
+            MenuAction::SfxVolume,
+            MenuAction::ScreenShake,
+            MenuAction::StarfieldDensity,
+            MenuAction::GamepadDeadzone,
+            MenuAction::DisplayMode,
+            MenuAction::Autofire,
+            MenuAction::Language,
+            MenuAction::Back,
+        ],
+        0,
+        GamePhase::Settings,
+    );
+
+    // Instructions text
+    commands.spawn((
+        Text::new(settings.bindings.hint("settings.hint", &localization)),
+        BindingHint("settings.hint"),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(480.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Settings),
+    ));
+}
+
+/// Step a level between 0.0 and `max` by `LEVEL_STEP`. Confirming (`step` 0)
+/// steps up and wraps around to 0.0 at the top.
+fn step_level(level: f32, step: i32, max: f32) -> f32 {
+    let next = match step {
+        0 if level >= max - LEVEL_STEP / 2.0 => 0.0,
+        0 => level + LEVEL_STEP,
+        _ => level + step as f32 * LEVEL_STEP,
+    };
+    // Round so that repeated steps land on whole tenths
+    (next.clamp(0.0, max) / LEVEL_STEP).round() * LEVEL_STEP
+}
+
+/// Change the settings from the settings screen menu
+pub fn settings_screen_input(
+    mut activated: MessageReader<MenuActivated>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    for MenuActivated { action, step } in activated.read() {
+        let step = *step;
+        match action {
+            MenuAction::MasterVolume => {
+                settings.master_volume = step_level(settings.master_volume, step, 1.0);
+            }
+            MenuAction::MusicVolume => {
+                settings.music_volume = step_level(settings.music_volume, step, 1.0);
+            }
+            MenuAction::SfxVolume => {
+                settings.sfx_volume = step_level(settings.sfx_volume, step, 1.0);
+            }
+            MenuAction::ScreenShake => {
+                settings.screen_shake = step_level(settings.screen_shake, step, 1.0);
+            }
+            MenuAction::StarfieldDensity => {
+                settings.starfield_density =
+                    step_level(settings.starfield_density, step, MAX_STARFIELD_DENSITY);
+            }
+            MenuAction::GamepadDeadzone => {
+                settings.gamepad_deadzone =
+                    step_level(settings.gamepad_deadzone, step, MAX_GAMEPAD_DEADZONE);
+            }
+            MenuAction::DisplayMode => {
+                settings.display_mode =
+                    settings
+                        .display_mode
+                        .cycle(if step == 0 { 1 } else { step });
+            }
+            MenuAction::Autofire => settings.autofire = !settings.autofire,
+            MenuAction::Language => {
+                let step = if step == 0 { 1 } else { step };
+                settings.language = Language::cycle(&settings.language, step).code.to_string();
+            }
+            MenuAction::Back if step == 0 => next_state.set(GamePhase::StartScreen),
+            _ => {}
+        }
+    }
+}
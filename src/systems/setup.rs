@@ -1,13 +1,30 @@
+use bevy::audio::Volume;
+use bevy::camera::ScalingMode;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
+use crate::achievements::*;
 use crate::components::*;
+use crate::config::*;
 use crate::constants::*;
+use crate::locale::*;
 use crate::resources::*;
+use crate::settings::*;
+use crate::systems::waves::*;
+use crate::waves::*;
 
 /// Initialize the camera and load textures
 pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera2d);
+    // The playfield keeps its size; fit_camera_to_window scales it to the window
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: PLAYFIELD_WIDTH,
+                height: PLAYFIELD_HEIGHT,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
 
     // Load textures
     let textures = GameTextures {
@@ -31,25 +48,26 @@ pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     };
 
     commands.insert_resource(audio);
-}
 
-/// Initialize window dimensions from actual window size
-pub fn initialize_window_dimensions(
-    mut window_dims: ResMut<WindowDimensions>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-) {
-    if let Ok(window) = window_query.single() {
-        window_dims.width = window.width();
-        window_dims.height = window.height();
-    }
+    // Load tunable gameplay values, the wave campaign, the achievements and
+    // every translation, so that switching language is instant
+    commands.insert_resource(GameConfigHandle(asset_server.load(GAME_CONFIG_PATH)));
+    commands.insert_resource(CampaignHandle(asset_server.load(CAMPAIGN_PATH)));
+    commands.insert_resource(AchievementListHandle(asset_server.load(ACHIEVEMENTS_PATH)));
+    commands.insert_resource(LocaleHandles(
+        LANGUAGES
+            .iter()
+            .map(|language| asset_server.load(language.path()))
+            .collect(),
+    ));
 }
 
-/// Spawn the player ship and score UI
+/// Spawn the player ship and start the music
 pub fn spawn_player(
     mut commands: Commands,
     textures: Res<GameTextures>,
     audio: Res<GameAudio>,
-    window_dims: Res<WindowDimensions>,
+    settings: Res<Settings>,
 ) {
     commands.spawn((
         Sprite {
@@ -57,70 +75,39 @@ pub fn spawn_player(
             custom_size: Some(Vec2::new(PLAYER_SIZE, PLAYER_SIZE)),
             ..default()
         },
-        Transform::from_xyz(0.0, -window_dims.height / 2.0 + 50.0, 0.0),
+        Transform::from_xyz(0.0, -PLAYFIELD_HEIGHT / 2.0 + 50.0, 0.0),
         Player,
-    ));
-
-    // Spawn score text
-    commands.spawn((
-        Text::new("Score: 0"),
-        TextFont {
-            font_size: 30.0,
-            ..default()
-        },
-        TextColor(Color::WHITE),
-        TextLayout::default(),
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
-            left: Val::Px(10.0),
-            ..default()
-        },
-        Score,
+        Hitbox::new(
+            Vec2::splat(PLAYER_SIZE),
+            CollisionLayer::PLAYER,
+            CollisionLayer::PICKUP,
+        )
+        .with_shrink(PLAYER_HITBOX_SHRINK),
+        DespawnOnExit(GamePhase::Playing),
     ));
 
     // Start background music
     commands.spawn((
         AudioPlayer::new(audio.game_music.clone()),
-        PlaybackSettings::LOOP,
+        PlaybackSettings::LOOP.with_volume(Volume::Linear(settings.music_level())),
         BackgroundMusic,
+        DespawnOnExit(GamePhase::Playing),
     ));
 }
 
-/// Spawn the enemy formation
+/// Spawn the enemy formation of the first wave
 pub fn spawn_enemies(
     mut commands: Commands,
     textures: Res<GameTextures>,
-    window_dims: Res<WindowDimensions>,
+    config: Res<GameConfig>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign_handle: Res<CampaignHandle>,
+    mut current_wave: ResMut<CurrentWave>,
 ) {
-    let start_x = -(ENEMY_COLS as f32 - 1.0) * ENEMY_SPACING / 2.0;
-    let start_y = window_dims.height / 2.0 - 100.0;
-
-    for row in 0..ENEMY_ROWS {
-        for col in 0..ENEMY_COLS {
-            let x = start_x + col as f32 * ENEMY_SPACING;
-            let y = start_y - row as f32 * ENEMY_SPACING;
-
-            // Use different enemy types for different rows
-            let enemy_texture = match row {
-                0 => textures.enemy3.clone(),
-                1..=2 => textures.enemy2.clone(),
-                _ => textures.enemy1.clone(),
-            };
+    // Fall back to the configured rectangular formation until the campaign has loaded
+    let definition = campaign_wave(&campaigns, &campaign_handle, 0)
+        .unwrap_or_else(|| WaveDefinition::from_config(&config.enemy));
 
-            commands.spawn((
-                Sprite {
-                    image: enemy_texture,
-                    custom_size: Some(Vec2::new(ENEMY_SIZE, ENEMY_SIZE)),
-                    ..default()
-                },
-                Transform::from_xyz(x, y, 0.0),
-                Enemy { original_x: x },
-                Velocity {
-                    x: ENEMY_SPEED,
-                    y: 0.0,
-                },
-            ));
-        }
-    }
+    spawn_formation(&mut commands, &textures, config.enemy.spacing, &definition);
+    *current_wave = CurrentWave::new(0, definition);
 }
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::constants::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+
+/// Build up screen shake when enemies are destroyed or the player is hit
+pub fn add_screen_shake(
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut player_hit: MessageReader<PlayerHit>,
+    mut shake: ResMut<ScreenShake>,
+) {
+    for _ in enemy_killed.read() {
+        shake.add(SCREEN_SHAKE_ENEMY_KILLED);
+    }
+    for _ in player_hit.read() {
+        shake.add(SCREEN_SHAKE_PLAYER_HIT);
+    }
+}
+
+/// Offset the camera by the current shake, scaled by the screen shake setting
+pub fn shake_camera(
+    mut shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let Ok(mut transform) = camera_query.single_mut() else {
+        return;
+    };
+
+    let strength = shake.trauma.powi(2) * settings.screen_shake * SCREEN_SHAKE_MAX_OFFSET;
+    let mut rng = rand::rng();
+    transform.translation.x = strength * rng.random_range(-1.0..1.0);
+    transform.translation.y = strength * rng.random_range(-1.0..1.0);
+
+    shake.trauma = (shake.trauma - SCREEN_SHAKE_DECAY * time.delta_secs()).max(0.0);
+}
@@ -0,0 +1,301 @@
+use bevy::asset::LoadState;
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::config::*;
+use crate::daily::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::snapshot::*;
+use crate::storage::*;
+use crate::systems::waves::*;
+use crate::waves::*;
+
+/// Components saved for each surviving enemy
+type EnemyComponents<'a> = (
+    &'a Transform,
+    &'a Enemy,
+    &'a EnemyKind,
+    Option<&'a Velocity>,
+    Has<Saucer>,
+);
+
+/// The content a run is played with: the game config and the campaign
+#[derive(SystemParam)]
+pub struct RunContent<'w> {
+    config: Res<'w, GameConfig>,
+    campaigns: Res<'w, Assets<Campaign>>,
+    campaign_handle: Res<'w, CampaignHandle>,
+    config_handle: Option<Res<'w, GameConfigHandle>>,
+    asset_server: Option<Res<'w, AssetServer>>,
+}
+
+impl RunContent<'_> {
+    /// Fingerprint of the config and campaign in use
+    pub fn fingerprint(&self) -> u64 {
+        let campaign = self
+            .campaigns
+            .get(&self.campaign_handle.0)
+            .map_or(0, |campaign| campaign.fingerprint);
+        [self.config.fingerprint, campaign]
+            .iter()
+            .fold(FNV_OFFSET_BASIS, |hash, part| {
+                fnv1a(hash, &part.to_le_bytes())
+            })
+    }
+
+    /// Whether the config and campaign have finished loading, or failed to, so
+    /// that the fingerprint will not change on its own any more
+    pub fn settled(&self) -> bool {
+        let Some(asset_server) = &self.asset_server else {
+            return true;
+        };
+        let config = self
+            .config_handle
+            .as_ref()
+            .map(|handle| handle.0.id().untyped());
+        [Some(self.campaign_handle.0.id().untyped()), config]
+            .into_iter()
+            .flatten()
+            .all(|id| {
+                matches!(
+                    asset_server.load_state(id),
+                    LoadState::Loaded | LoadState::Failed(_)
+                )
+            })
+    }
+}
+
+/// Everything a `RunSnapshot` is captured from
+#[derive(SystemParam)]
+pub struct RunCapture<'w, 's> {
+    player_query: Query<'w, 's, &'static Transform, With<Player>>,
+    enemy_query: Query<'w, 's, EnemyComponents<'static>>,
+    bullet_query: Query<'w, 's, (&'static Transform, &'static Velocity), With<Bullet>>,
+    enemy_bullet_query: Query<'w, 's, (&'static Transform, &'static Velocity), With<EnemyBullet>>,
+    game_state: Res<'w, GameState>,
+    current_wave: Res<'w, CurrentWave>,
+    run_stats: Res<'w, RunStats>,
+    difficulty: Res<'w, Difficulty>,
+    rng: Res<'w, GameRng>,
+    run_mode: Res<'w, RunMode>,
+    input_log: Res<'w, InputLog>,
+    content: RunContent<'w>,
+}
+
+impl RunCapture<'_, '_> {
+    /// Capture the run in progress
+    pub fn snapshot(&self) -> RunSnapshot {
+        let bullet = |(transform, velocity): (&Transform, &Velocity)| BulletSnapshot {
+            position: transform.translation.truncate().to_array(),
+            velocity: [velocity.x, velocity.y],
+        };
+
+        RunSnapshot {
+            player: self
+                .player_query
+                .single()
+                .ok()
+                .map(|transform| transform.translation.truncate().to_array()),
+            enemies: self
+                .enemy_query
+                .iter()
+                .map(|(transform, enemy, kind, velocity, saucer)| EnemySnapshot {
+                    kind: *kind,
+                    position: transform.translation.truncate().to_array(),
+                    original_x: enemy.original_x,
+                    velocity: velocity.map_or([0.0; 2], |velocity| [velocity.x, velocity.y]),
+                    saucer,
+                })
+                .collect(),
+            bullets: self.bullet_query.iter().map(bullet).collect(),
+            enemy_bullets: self.enemy_bullet_query.iter().map(bullet).collect(),
+            game_state: self.game_state.clone(),
+            wave: WaveSnapshot {
+                index: self.current_wave.index,
+                elapsed: self.current_wave.elapsed,
+                next_special_spawn: self.current_wave.next_special_spawn,
+            },
+            stats: self.run_stats.stats.clone(),
+            difficulty: *self.difficulty,
+            rng: self.rng.clone(),
+            mode: *self.run_mode,
+            input_log: self.input_log.clone(),
+            content: self.content.fingerprint(),
+        }
+    }
+}
+
+/// Whether "Continue" was chosen and the suspended run is being restored
+pub fn resuming_run(suspended: Res<SuspendedRun>) -> bool {
+    suspended.resuming
+}
+
+/// Load the suspended run saved by a previous session
+pub fn load_suspended_run(storage: Res<Storage>, mut suspended: ResMut<SuspendedRun>) {
+    *suspended = storage.load();
+}
+
+/// Drop the suspended run once the config and campaign have loaded if either
+/// has changed since it was suspended, and rebuild the start screen without
+/// Continue
+pub fn discard_incompatible_run(
+    content: RunContent,
+    storage: Res<Storage>,
+    mut suspended: ResMut<SuspendedRun>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    let Some(snapshot) = &suspended.snapshot else {
+        return;
+    };
+    if !content.settled() || snapshot.content == content.fingerprint() {
+        return;
+    }
+
+    info!("Discarding the suspended run: the game config or campaign has changed");
+    suspended.snapshot = None;
+    if let Err(error) = storage.remove(SuspendedRun::KEY) {
+        warn!("Could not remove the suspended run: {error}");
+    }
+    next_state.set(GamePhase::StartScreen);
+}
+
+/// Save the run and return to the start screen on Suspend in the pause menu
+pub fn suspend_run(
+    mut activated: MessageReader<MenuActivated>,
+    capture: RunCapture,
+    storage: Res<Storage>,
+    mut suspended: ResMut<SuspendedRun>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    if activated
+        .read()
+        .any(|activated| activated.action == MenuAction::Suspend && activated.step == 0)
+    {
+        suspended.snapshot = Some(capture.snapshot());
+        storage.save(&*suspended);
+        next_state.set(GamePhase::StartScreen);
+    }
+}
+
+/// Save the run when the app closes mid-game
+pub fn suspend_run_on_exit(
+    mut app_exit: MessageReader<AppExit>,
+    capture: RunCapture,
+    storage: Res<Storage>,
+    mut suspended: ResMut<SuspendedRun>,
+) {
+    if app_exit.read().count() > 0 {
+        suspended.snapshot = Some(capture.snapshot());
+        storage.save(&*suspended);
+    }
+}
+
+/// Drop the suspended run when a new game starts, so only one run is ever in progress
+pub fn discard_suspended_run(storage: Res<Storage>, mut suspended: ResMut<SuspendedRun>) {
+    if suspended.snapshot.take().is_some()
+        && let Err(error) = storage.remove(SuspendedRun::KEY)
+    {
+        warn!("Could not remove the suspended run: {error}");
+    }
+}
+
+/// Rebuild the suspended run. Runs after `spawn_player`, in place of `spawn_enemies`.
+#[allow(clippy::too_many_arguments)]
+pub fn restore_run(
+    mut commands: Commands,
+    mut suspended: ResMut<SuspendedRun>,
+    storage: Res<Storage>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut pools: ResMut<EntityPools>,
+    textures: Res<GameTextures>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign_handle: Res<CampaignHandle>,
+    config: Res<GameConfig>,
+    mut game_state: ResMut<GameState>,
+    mut current_wave: ResMut<CurrentWave>,
+    mut run_stats: ResMut<RunStats>,
+    mut difficulty: ResMut<Difficulty>,
+    mut rng: ResMut<GameRng>,
+    mut run_mode: ResMut<RunMode>,
+    mut input_log: ResMut<InputLog>,
+) {
+    suspended.resuming = false;
+    // A run can only be continued once
+    let Some(snapshot) = suspended.snapshot.take() else {
+        return;
+    };
+    if let Err(error) = storage.remove(SuspendedRun::KEY) {
+        warn!("Could not remove the suspended run: {error}");
+    }
+
+    if let (Some([x, y]), Ok(mut transform)) = (snapshot.player, player_query.single_mut()) {
+        transform.translation.x = x;
+        transform.translation.y = y;
+    }
+
+    for enemy in &snapshot.enemies {
+        let [x, y] = enemy.position;
+        let entity = spawn_enemy(&mut commands, &textures, enemy.kind, Vec2::new(x, y), 0.0);
+        commands.entity(entity).insert((
+            Enemy {
+                original_x: enemy.original_x,
+            },
+            Velocity {
+                x: enemy.velocity[0],
+                y: enemy.velocity[1],
+            },
+        ));
+        if enemy.saucer {
+            commands.entity(entity).insert(Saucer);
+        }
+    }
+
+    let pools = &mut *pools;
+    for (bullets, pool) in [
+        (&snapshot.bullets, &mut pools.bullets),
+        (&snapshot.enemy_bullets, &mut pools.enemy_bullets),
+    ] {
+        for bullet in bullets {
+            let Some(entity) = pool.acquire() else {
+                break;
+            };
+            let [x, y] = bullet.position;
+            commands
+                .entity(entity)
+                .insert((
+                    Transform::from_xyz(x, y, 0.0),
+                    Velocity {
+                        x: bullet.velocity[0],
+                        y: bullet.velocity[1],
+                    },
+                ))
+                .remove::<Disabled>();
+        }
+    }
+
+    // Runs whose campaign changed are discarded before they can be continued,
+    // but fall back like `spawn_enemies` does if the wave still cannot be found
+    let definition = campaign_wave(&campaigns, &campaign_handle, snapshot.wave.index)
+        .unwrap_or_else(|| WaveDefinition::from_config(&config.enemy));
+    *current_wave = CurrentWave {
+        elapsed: snapshot.wave.elapsed,
+        next_special_spawn: snapshot
+            .wave
+            .next_special_spawn
+            .min(definition.special_spawns.len()),
+        ..CurrentWave::new(snapshot.wave.index, definition)
+    };
+
+    *game_state = snapshot.game_state;
+    *run_stats = RunStats {
+        stats: snapshot.stats,
+        outcome: None,
+    };
+    *difficulty = snapshot.difficulty;
+    *rng = snapshot.rng;
+    *run_mode = snapshot.mode;
+    *input_log = snapshot.input_log;
+}
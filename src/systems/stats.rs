@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::settings::*;
+use crate::storage::*;
+use crate::systems::input::*;
+
+/// Format seconds as `m:ss`
+pub fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Start a fresh set of run statistics
+pub fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = RunStats::default();
+}
+
+/// Count shots, kills, cleared waves and the run outcome from gameplay messages
+pub fn collect_run_stats(
+    mut shot_fired: MessageReader<ShotFired>,
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut wave_cleared: MessageReader<WaveCleared>,
+    mut run_ended: MessageReader<RunEnded>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let run_stats = &mut *run_stats;
+    let stats = &mut run_stats.stats;
+
+    for shot in shot_fired.read() {
+        if shot.shooter == Shooter::Player {
+            stats.shots_fired += 1;
+        }
+    }
+
+    for kill in enemy_killed.read() {
+        stats.hits += 1;
+        stats.kills.add(kill.kind);
+        if kill.saucer {
+            stats.saucers_hit += 1;
+        }
+    }
+
+    stats.waves_cleared += wave_cleared.read().count() as u32;
+
+    // Only the first way the run ended counts
+    for ended in run_ended.read() {
+        if run_stats.outcome.is_none() {
+            run_stats.outcome = Some(ended.outcome);
+            stats.outcomes.add(ended.outcome);
+        }
+    }
+}
+
+/// Add the time spent playing to the run statistics
+pub fn track_time_survived(mut run_stats: ResMut<RunStats>, time: Res<Time>) {
+    run_stats.stats.time_survived += time.delta_secs();
+}
+
+/// Add the finished run to the lifetime statistics and save them
+pub fn record_lifetime_stats(
+    mut run_ended: MessageReader<RunEnded>,
+    run_stats: Res<RunStats>,
+    game_state: Res<GameState>,
+    mut lifetime_stats: ResMut<LifetimeStats>,
+    storage: Res<Storage>,
+) {
+    if run_ended.read().count() == 0 {
+        return;
+    }
+
+    lifetime_stats.record(&run_stats.stats, game_state.score);
+    storage.save(&*lifetime_stats);
+}
+
+/// Setup the lifetime statistics screen
+pub fn setup_stats_screen(
+    mut commands: Commands,
+    lifetime_stats: Res<LifetimeStats>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+) {
+    let totals = &lifetime_stats.totals;
+    let number = |value: u32| localization.number(value.into());
+
+    // Title text
+    commands.spawn((
+        Text::new(localization.text("stats.title")),
+        TextFont {
+            font_size: 50.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.84, 0.0)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(50.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        DespawnOnExit(GamePhase::Stats),
+    ));
+
+    // Lifetime totals
+    let lines = [
+        localization.plural("stats.games_played", lifetime_stats.runs.into(), &[]),
+        localization.format(
+            "stats.best_score",
+            &[("score", &number(lifetime_stats.best_score))],
+        ),
+        localization.format(
+            "stats.time_played",
+            &[("time", &format_duration(totals.time_survived))],
+        ),
+        localization.format(
+            "stats.waves_cleared",
+            &[("count", &number(totals.waves_cleared))],
+        ),
+        localization.format(
+            "stats.shots",
+            &[
+                ("shots", &number(totals.shots_fired)),
+                ("hits", &number(totals.hits)),
+                ("accuracy", &localization.percent(totals.accuracy())),
+            ],
+        ),
+        localization.format(
+            "stats.kills",
+            &[
+                ("type1", &number(totals.kills.type1)),
+                ("type2", &number(totals.kills.type2)),
+                ("type3", &number(totals.kills.type3)),
+            ],
+        ),
+        localization.format("stats.saucers", &[("count", &number(totals.saucers_hit))]),
+        localization.format(
+            "stats.outcomes",
+            &[
+                ("victories", &number(totals.outcomes.victories)),
+                ("shot", &number(totals.outcomes.shot)),
+                ("invaded", &number(totals.outcomes.invaded)),
+            ],
+        ),
+    ];
+    for (i, line) in lines.into_iter().enumerate() {
+        commands.spawn((
+            Text::new(line),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(140.0 + i as f32 * 36.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            DespawnOnExit(GamePhase::Stats),
+        ));
+    }
+
+    // Return text
+    commands.spawn((
+        Text::new(settings.bindings.hint("common.return", &localization)),
+        BindingHint("common.return"),
+        TextFont {
+            font_size: 25.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(480.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        Button,
+        TapButton(TapTarget::Action(Action::Back)),
+        DespawnOnExit(GamePhase::Stats),
+    ));
+}
+
+/// Handle statistics screen input
+pub fn stats_screen_input(controls: PlayerControls, mut next_state: ResMut<NextState<GamePhase>>) {
+    if controls.just_pressed(Action::Back) {
+        next_state.set(GamePhase::StartScreen);
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::constants::*;
+use crate::input::*;
+use crate::locale::*;
+use crate::resources::*;
+
+/// Spawn the on-screen button bar used with touch input, hidden until touch mode
+/// is on
+pub fn spawn_touch_controls(
+    mut commands: Commands,
+    pointer: Res<PointerInput>,
+    localization: Res<Localization>,
+) {
+    let visibility = if pointer.mode == PointerMode::Touch {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Px(TOUCH_BAR_HEIGHT),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::horizontal(Val::Px(16.0)),
+                ..default()
+            },
+            visibility,
+            TouchControls,
+            DespawnOnExit(GamePhase::Playing),
+        ))
+        .with_children(|bar| {
+            for (action, width) in [(Action::Pause, 140.0), (Action::Fire, 200.0)] {
+                bar.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(width),
+                        height: Val::Px(TOUCH_BAR_HEIGHT - 20.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+                    TapButton(TapTarget::Action(action)),
+                ))
+                .with_child((
+                    Text::new(localization.text(action.key()).to_uppercase()),
+                    TextFont {
+                        font_size: 26.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            }
+        });
+}
+
+/// Show the button bar once touch mode turns on during a run
+pub fn show_touch_controls(
+    pointer: Res<PointerInput>,
+    mut query: Query<&mut Visibility, With<TouchControls>>,
+) {
+    if pointer.mode != PointerMode::Touch {
+        return;
+    }
+    for mut visibility in query.iter_mut() {
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/// Spawn a centered row of tappable labels at `top`, for menus that would
+/// otherwise need a keyboard
+pub fn spawn_tap_row(
+    commands: &mut Commands,
+    top: f32,
+    items: &[(String, TapTarget)],
+    phase: GamePhase,
+) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(top),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(24.0),
+                ..default()
+            },
+            DespawnOnExit(phase),
+        ))
+        .with_children(|row| {
+            for (label, target) in items {
+                row.spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.1)),
+                    TapButton(*target),
+                ))
+                .with_child((
+                    Text::new(label.clone()),
+                    TextFont {
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            }
+        });
+}
@@ -1,13 +1,360 @@
 use bevy::prelude::*;
 
 use crate::components::*;
+use crate::constants::*;
+use crate::locale::*;
+use crate::messages::*;
 use crate::resources::*;
 
-/// Update the score display
-pub fn update_score_display(game_state: Res<GameState>, mut query: Query<&mut Text, With<Score>>) {
-    if game_state.is_changed() {
-        for mut text in query.iter_mut() {
-            **text = format!("Score: {}", game_state.score);
+fn hud_text(text: String, size: f32, color: Color) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: size,
+            ..default()
+        },
+        TextColor(color),
+    )
+}
+
+fn hud_node(size: Vec2) -> Node {
+    Node {
+        width: Val::Px(size.x),
+        height: Val::Px(size.y),
+        ..default()
+    }
+}
+
+fn score_label(score: u32, localization: &Localization) -> String {
+    localization.format(
+        "hud.score",
+        &[("score", &localization.number(score.into()))],
+    )
+}
+
+fn high_score_label(
+    game_state: &GameState,
+    high_scores: &HighScores,
+    localization: &Localization,
+) -> String {
+    let best = high_scores
+        .get_top_scores()
+        .first()
+        .map_or(0, |entry| entry.score);
+    let best = localization.number(best.max(game_state.score).into());
+    localization.format("hud.high_score", &[("score", &best)])
+}
+
+fn wave_label(current_wave: &CurrentWave, localization: &Localization) -> String {
+    localization.format("hud.wave", &[("wave", &(current_wave.index + 1))])
+}
+
+fn status_label(active_effects: &ActiveEffects, localization: &Localization) -> String {
+    active_effects
+        .effects
+        .iter()
+        .map(|effect| {
+            localization.format(
+                "hud.effect",
+                &[
+                    ("name", &localization.text(&effect.name)),
+                    ("time", &effect.remaining.ceil()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn life_icon(textures: &GameTextures) -> impl Bundle {
+    (
+        ImageNode::new(textures.player.clone()),
+        hud_node(Vec2::splat(HUD_LIFE_ICON_SIZE)),
+        LifeIcon,
+    )
+}
+
+/// Spawn the in-game HUD: score, high score and wave along the top, ship icons
+/// on the right, the combo meter and status below. Runs after the run is reset
+/// or restored, so every widget starts with the current values.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_hud(
+    mut commands: Commands,
+    textures: Res<GameTextures>,
+    game_state: Res<GameState>,
+    high_scores: Res<HighScores>,
+    current_wave: Res<CurrentWave>,
+    active_effects: Res<ActiveEffects>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(HUD_PADDING)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            HudRoot,
+            DespawnOnExit(GamePhase::Playing),
+        ))
+        .with_children(|hud| {
+            // Top row: score, high score and wave, ship icons
+            hud.spawn(Node {
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    hud_text(
+                        score_label(game_state.score, &localization),
+                        30.0,
+                        Color::WHITE,
+                    ),
+                    Score,
+                ));
+                row.spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|center| {
+                    center.spawn((
+                        hud_text(
+                            high_score_label(&game_state, &high_scores, &localization),
+                            22.0,
+                            Color::srgb(1.0, 0.84, 0.0),
+                        ),
+                        HighScoreText,
+                    ));
+                    center.spawn((
+                        hud_text(
+                            wave_label(&current_wave, &localization),
+                            20.0,
+                            Color::srgb(0.0, 1.0, 0.5),
+                        ),
+                        WaveText,
+                    ));
+                });
+                row.spawn((
+                    Node {
+                        column_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    LivesDisplay,
+                ))
+                .with_children(|lives| {
+                    for _ in 0..game_state.lives {
+                        lives.spawn(life_icon(&textures));
+                    }
+                });
+            });
+
+            // Second row: combo meter, status
+            hud.spawn(Node {
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|combo| {
+                    combo.spawn((
+                        hud_text(String::new(), 20.0, Color::srgb(1.0, 0.5, 0.0)),
+                        ComboText,
+                    ));
+                    combo
+                        .spawn((
+                            hud_node(Vec2::new(HUD_COMBO_BAR_WIDTH, HUD_COMBO_BAR_HEIGHT)),
+                            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.2)),
+                            Visibility::Hidden,
+                            ComboBar,
+                        ))
+                        .with_child((
+                            Node {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(1.0, 0.5, 0.0)),
+                            ComboBarFill,
+                        ));
+                });
+                row.spawn((
+                    hud_text(
+                        status_label(&active_effects, &localization),
+                        18.0,
+                        Color::srgb(0.7, 0.7, 0.7),
+                    ),
+                    StatusText,
+                ));
+            });
+        });
+}
+
+/// Update the score display when the score changes
+pub fn update_score_display(
+    game_state: Res<GameState>,
+    localization: Res<Localization>,
+    mut shown: Local<Option<u32>>,
+    mut query: Query<&mut Text, With<Score>>,
+) {
+    if *shown == Some(game_state.score) {
+        return;
+    }
+    *shown = Some(game_state.score);
+    for mut text in query.iter_mut() {
+        **text = score_label(game_state.score, &localization);
+    }
+}
+
+/// Update the high score display, which the current score may beat
+pub fn update_high_score_display(
+    game_state: Res<GameState>,
+    high_scores: Res<HighScores>,
+    localization: Res<Localization>,
+    mut shown: Local<String>,
+    mut query: Query<&mut Text, With<HighScoreText>>,
+) {
+    let label = high_score_label(&game_state, &high_scores, &localization);
+    if *shown == label {
+        return;
+    }
+    for mut text in query.iter_mut() {
+        **text = label.clone();
+    }
+    *shown = label;
+}
+
+/// Update the wave number when a new wave starts
+pub fn update_wave_display(
+    current_wave: Res<CurrentWave>,
+    localization: Res<Localization>,
+    mut shown: Local<Option<usize>>,
+    mut query: Query<&mut Text, With<WaveText>>,
+) {
+    if *shown == Some(current_wave.index) {
+        return;
+    }
+    *shown = Some(current_wave.index);
+    for mut text in query.iter_mut() {
+        **text = wave_label(&current_wave, &localization);
+    }
+}
+
+/// Add or remove ship icons when the number of lives changes
+pub fn update_lives_display(
+    mut commands: Commands,
+    textures: Res<GameTextures>,
+    game_state: Res<GameState>,
+    displays: Query<Entity, With<LivesDisplay>>,
+    icons: Query<Entity, With<LifeIcon>>,
+) {
+    let lives = game_state.lives as usize;
+    let shown = icons.iter().count();
+    for icon in icons.iter().skip(lives) {
+        commands.entity(icon).despawn();
+    }
+    for display in displays.iter() {
+        for _ in shown..lives {
+            commands.entity(display).with_child(life_icon(&textures));
         }
     }
 }
+
+/// Update the status list when an effect starts, ticks or wears off
+pub fn update_status_display(
+    active_effects: Res<ActiveEffects>,
+    localization: Res<Localization>,
+    mut query: Query<&mut Text, With<StatusText>>,
+) {
+    if !active_effects.is_changed() {
+        return;
+    }
+    for mut text in query.iter_mut() {
+        **text = status_label(&active_effects, &localization);
+    }
+}
+
+/// Start a new run without active effects
+pub fn reset_active_effects(mut active_effects: ResMut<ActiveEffects>) {
+    *active_effects = ActiveEffects::default();
+}
+
+/// Count down the active effects and drop the ones that wore off
+pub fn tick_active_effects(mut active_effects: ResMut<ActiveEffects>, time: Res<Time>) {
+    if active_effects.effects.is_empty() {
+        return;
+    }
+    for effect in active_effects.effects.iter_mut() {
+        effect.remaining -= time.delta_secs();
+    }
+    active_effects
+        .effects
+        .retain(|effect| effect.remaining > 0.0);
+}
+
+/// Start a new run without a combo
+pub fn reset_combo(mut combo: ResMut<Combo>) {
+    *combo = Combo::default();
+}
+
+/// Extend the combo on every kill and let it run out `COMBO_WINDOW` seconds
+/// after the last one
+pub fn update_combo(
+    mut enemy_killed: MessageReader<EnemyKilled>,
+    mut combo: ResMut<Combo>,
+    time: Res<Time>,
+) {
+    let kills = enemy_killed.read().count() as u32;
+    if kills > 0 {
+        combo.count += kills;
+        combo.remaining = COMBO_WINDOW;
+    } else if combo.remaining > 0.0 {
+        combo.remaining -= time.delta_secs();
+        if combo.remaining <= 0.0 {
+            *combo = Combo::default();
+        }
+    }
+}
+
+/// Show the combo from two kills on, with a bar of the time left to extend it
+pub fn update_combo_display(
+    combo: Res<Combo>,
+    localization: Res<Localization>,
+    mut texts: Query<&mut Text, With<ComboText>>,
+    mut bars: Query<&mut Visibility, With<ComboBar>>,
+    mut fills: Query<&mut Node, With<ComboBarFill>>,
+) {
+    if !combo.is_changed() {
+        return;
+    }
+
+    let active = combo.count >= 2;
+    for mut text in texts.iter_mut() {
+        **text = if active {
+            localization.format("hud.combo", &[("count", &combo.count)])
+        } else {
+            String::new()
+        };
+    }
+    for mut visibility in bars.iter_mut() {
+        visibility.set_if_neq(if active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    for mut node in fills.iter_mut() {
+        node.width = Val::Percent(combo.remaining / COMBO_WINDOW * 100.0);
+    }
+}
@@ -1,16 +1,16 @@
 use bevy::prelude::*;
 
 use crate::components::*;
+use crate::messages::*;
 use crate::resources::*;
 
-/// Check if all enemies are destroyed (victory condition)
+/// Check if all enemies of the current wave are destroyed
 pub fn check_all_enemies_destroyed(
     enemy_query: Query<Entity, With<Enemy>>,
-    mut next_state: ResMut<NextState<GamePhase>>,
-    mut game_state: ResMut<GameState>,
+    current_wave: Res<CurrentWave>,
+    mut wave_cleared: MessageWriter<WaveCleared>,
 ) {
-    if enemy_query.is_empty() {
-        game_state.victory = true;
-        next_state.set(GamePhase::GameOver);
+    if enemy_query.is_empty() && current_wave.all_spawned() {
+        wave_cleared.write(WaveCleared);
     }
 }
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::config::*;
+use crate::constants::*;
+use crate::messages::*;
+use crate::resources::*;
+use crate::waves::*;
+
+/// Look up a campaign wave, if the campaign has loaded and has that many waves
+pub fn campaign_wave(
+    campaigns: &Assets<Campaign>,
+    handle: &CampaignHandle,
+    index: usize,
+) -> Option<WaveDefinition> {
+    campaigns
+        .get(&handle.0)
+        .and_then(|campaign| campaign.waves.get(index))
+        .cloned()
+}
+
+/// Spawn a single enemy at a position and return it
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    textures: &GameTextures,
+    kind: EnemyKind,
+    position: Vec2,
+    speed: f32,
+) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                image: textures.enemy(kind),
+                custom_size: Some(Vec2::new(ENEMY_SIZE, ENEMY_SIZE)),
+                ..default()
+            },
+            Transform::from_xyz(position.x, position.y, 0.0),
+            Enemy {
+                original_x: position.x,
+            },
+            Hitbox::new(
+                Vec2::splat(ENEMY_SIZE),
+                CollisionLayer::ENEMY,
+                CollisionLayer::NONE,
+            )
+            .with_shrink(ENEMY_HITBOX_SHRINK),
+            Velocity { x: speed, y: 0.0 },
+            kind,
+            DespawnOnExit(GamePhase::Playing),
+        ))
+        .id()
+}
+
+/// Spawn the formation of a wave, centered horizontally below the top of the screen
+pub fn spawn_formation(
+    commands: &mut Commands,
+    textures: &GameTextures,
+    spacing: f32,
+    wave: &WaveDefinition,
+) {
+    let start_x = -(wave.columns() as f32 - 1.0) * spacing / 2.0;
+    let start_y = PLAYFIELD_HEIGHT / 2.0 - 100.0;
+
+    for (row, col, kind) in wave.slots() {
+        let position = Vec2::new(
+            start_x + col as f32 * spacing,
+            start_y - row as f32 * spacing,
+        );
+        spawn_enemy(commands, textures, kind, position, wave.speed.start);
+    }
+}
+
+/// Spawn the next campaign wave when one is cleared, or end the game in victory
+/// after the last one
+#[allow(clippy::too_many_arguments)]
+pub fn advance_wave(
+    mut commands: Commands,
+    mut wave_cleared: MessageReader<WaveCleared>,
+    mut current_wave: ResMut<CurrentWave>,
+    mut game_state: ResMut<GameState>,
+    mut run_ended: MessageWriter<RunEnded>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign_handle: Res<CampaignHandle>,
+    textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+) {
+    if wave_cleared.read().count() == 0 {
+        return;
+    }
+
+    let next_index = current_wave.index + 1;
+    match campaign_wave(&campaigns, &campaign_handle, next_index) {
+        Some(definition) => {
+            spawn_formation(&mut commands, &textures, config.enemy.spacing, &definition);
+            *current_wave = CurrentWave::new(next_index, definition);
+            game_state.enemy_direction = 1.0;
+            game_state.enemy_shoot_timer = 0.0;
+        }
+        None => {
+            game_state.victory = true;
+            run_ended.write(RunEnded {
+                outcome: RunOutcome::Victory,
+            });
+        }
+    }
+}
+
+/// Spawn the timed extra enemies of the current wave
+pub fn spawn_special_enemies(
+    mut commands: Commands,
+    mut current_wave: ResMut<CurrentWave>,
+    textures: Res<GameTextures>,
+    time: Res<Time>,
+) {
+    current_wave.elapsed += time.delta_secs();
+
+    while let Some(spawn) = current_wave
+        .definition
+        .special_spawns
+        .get(current_wave.next_special_spawn)
+        .copied()
+    {
+        if spawn.time > current_wave.elapsed {
+            break;
+        }
+
+        let speed = current_wave.definition.speed.start;
+        let enemy = spawn_enemy(
+            &mut commands,
+            &textures,
+            spawn.kind,
+            Vec2::new(spawn.position.0, spawn.position.1),
+            speed,
+        );
+        commands.entity(enemy).insert(Saucer);
+        current_wave.next_special_spawn += 1;
+    }
+}
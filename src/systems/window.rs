@@ -0,0 +1,52 @@
+use bevy::camera::Viewport;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::constants::*;
+use crate::resources::*;
+
+/// Keep `WindowDimensions` in step with the primary window as it is resized
+pub fn update_window_dimensions(
+    mut window_dims: ResMut<WindowDimensions>,
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    // A minimized window has no size; keep the last one
+    if window.width() > 0.0 && window.height() > 0.0 {
+        window_dims.set_if_neq(WindowDimensions {
+            width: window.width(),
+            height: window.height(),
+        });
+    }
+}
+
+/// Show the playfield as large as the window allows, centered with bars on the
+/// sides that do not fit, and scale the UI with it
+pub fn fit_camera_to_window(
+    window_dims: Res<WindowDimensions>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Camera, With<Camera2d>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let scale = window_dims.playfield_scale();
+    ui_scale.0 = scale;
+
+    let window_size = window.physical_size();
+    let size = (Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT) * scale * window.scale_factor())
+        .round()
+        .as_uvec2()
+        .clamp(UVec2::ONE, window_size.max(UVec2::ONE));
+    let viewport = Viewport {
+        physical_position: window_size.saturating_sub(size) / 2,
+        physical_size: size,
+        ..default()
+    };
+    for mut camera in cameras.iter_mut() {
+        camera.viewport = Some(viewport.clone());
+    }
+}
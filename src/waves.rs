@@ -0,0 +1,227 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::components::EnemyKind;
+use crate::config::EnemyConfig;
+use crate::daily::{FNV_OFFSET_BASIS, fnv1a};
+
+/// Path of the campaign asset, relative to `resources/`
+pub const CAMPAIGN_PATH: &str = "campaign.waves.ron";
+
+/// Ordered list of waves played in one game, loaded from `resources/campaign.waves.ron`
+#[derive(Asset, TypePath, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    pub waves: Vec<WaveDefinition>,
+    /// Hash of the file the campaign was loaded from
+    #[serde(skip)]
+    pub fingerprint: u64,
+}
+
+/// One wave: formation layout, movement speed, firing behaviour and timed extra spawns.
+///
+/// The formation is a character grid read top to bottom. `1`, `2` and `3` place an
+/// enemy of that type, `.` or a space leaves the slot empty.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveDefinition {
+    pub formation: Vec<String>,
+    pub speed: SpeedCurve,
+    pub fire: FirePolicy,
+    #[serde(default)]
+    pub special_spawns: Vec<SpecialSpawn>,
+}
+
+/// Formation speed, interpolated from `start` to `end` as enemies are destroyed
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpeedCurve {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl SpeedCurve {
+    pub fn speed(&self, progress: f32) -> f32 {
+        self.start + (self.end - self.start) * progress.clamp(0.0, 1.0)
+    }
+}
+
+/// How often enemies fire and which enemy takes the shot
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FirePolicy {
+    pub interval: f32,
+    pub shooter: ShooterSelection,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ShooterSelection {
+    /// Any enemy may fire
+    Random,
+    /// Only enemies with no other enemy below them fire
+    Front,
+    /// The front enemy closest to the player fires
+    Aimed,
+}
+
+/// An enemy that joins the wave `time` seconds after it starts, at `position`
+/// relative to the center of the screen
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecialSpawn {
+    pub time: f32,
+    pub kind: EnemyKind,
+    pub position: (f32, f32),
+}
+
+impl WaveDefinition {
+    /// Build the classic rectangular wave from the game configuration, used when no
+    /// campaign is available
+    pub fn from_config(config: &EnemyConfig) -> Self {
+        let formation = (0..config.rows)
+            .map(|row| {
+                let symbol = match row {
+                    0 => '3',
+                    1..=2 => '2',
+                    _ => '1',
+                };
+                std::iter::repeat_n(symbol, config.cols).collect()
+            })
+            .collect();
+
+        Self {
+            formation,
+            speed: SpeedCurve {
+                start: config.speed,
+                end: config.speed,
+            },
+            fire: FirePolicy {
+                interval: config.shoot_interval,
+                shooter: ShooterSelection::Random,
+            },
+            special_spawns: Vec::new(),
+        }
+    }
+
+    /// Occupied formation slots as `(row, col, kind)`
+    pub fn slots(&self) -> impl Iterator<Item = (usize, usize, EnemyKind)> + '_ {
+        self.formation.iter().enumerate().flat_map(|(row, line)| {
+            line.chars().enumerate().filter_map(move |(col, symbol)| {
+                EnemyKind::from_symbol(symbol).map(|kind| (row, col, kind))
+            })
+        })
+    }
+
+    /// Width of the widest formation row, in slots
+    pub fn columns(&self) -> usize {
+        self.formation
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Number of enemies the wave spawns in total
+    pub fn enemy_count(&self) -> usize {
+        self.slots().count() + self.special_spawns.len()
+    }
+}
+
+/// Errors raised while loading a campaign
+#[derive(Debug, Error)]
+pub enum CampaignError {
+    #[error("could not read campaign: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse campaign: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("invalid campaign: wave {wave}: {reason}")]
+    Invalid { wave: usize, reason: String },
+}
+
+impl Campaign {
+    /// Check that every wave can be spawned and played
+    pub fn validate(&self) -> Result<(), CampaignError> {
+        if self.waves.is_empty() {
+            return Err(CampaignError::Invalid {
+                wave: 0,
+                reason: "campaign has no waves".to_string(),
+            });
+        }
+
+        for (index, wave) in self.waves.iter().enumerate() {
+            // Waves are numbered from 1 in messages, matching what players see
+            let invalid = |reason: String| CampaignError::Invalid {
+                wave: index + 1,
+                reason,
+            };
+
+            for (row, line) in wave.formation.iter().enumerate() {
+                for (col, symbol) in line.chars().enumerate() {
+                    let empty = symbol == '.' || symbol == ' ';
+                    if !empty && EnemyKind::from_symbol(symbol).is_none() {
+                        return Err(invalid(format!(
+                            "unknown formation symbol '{symbol}' at row {}, column {}",
+                            row + 1,
+                            col + 1
+                        )));
+                    }
+                }
+            }
+            if wave.enemy_count() == 0 {
+                return Err(invalid("wave has no enemies".to_string()));
+            }
+            let positive = |value: f32| value.is_finite() && value > 0.0;
+            if !(positive(wave.speed.start) && positive(wave.speed.end)) {
+                return Err(invalid("speed must be positive".to_string()));
+            }
+            if !positive(wave.fire.interval) {
+                return Err(invalid("fire interval must be positive".to_string()));
+            }
+            if wave.special_spawns.iter().any(|spawn| spawn.time < 0.0) {
+                return Err(invalid(
+                    "special spawn time must not be negative".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asset loader for `*.waves.ron` files
+#[derive(Default, TypePath)]
+pub struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Campaign, CampaignError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut campaign: Campaign = ron::de::from_bytes(&bytes)?;
+        campaign.validate()?;
+        campaign.fingerprint = fnv1a(FNV_OFFSET_BASIS, &bytes);
+
+        // Spawns are consumed in order while the wave runs
+        for wave in &mut campaign.waves {
+            wave.special_spawns
+                .sort_by(|a, b| a.time.total_cmp(&b.time));
+        }
+
+        Ok(campaign)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::achievements::*;
+use vibe_invaders::components::*;
+use vibe_invaders::messages::*;
+use vibe_invaders::waves::{CAMPAIGN_PATH, Campaign};
+
+fn achievement(id: &str, condition: AchievementCondition) -> AchievementDefinition {
+    AchievementDefinition {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        condition,
+    }
+}
+
+fn achievement_app(achievements: Vec<AchievementDefinition>) -> App {
+    let mut app = game_app();
+    app.insert_resource(AchievementList { achievements });
+    enter_empty_arena(&mut app);
+    app
+}
+
+fn is_unlocked(app: &App, id: &str) -> bool {
+    app.world()
+        .resource::<UnlockedAchievements>()
+        .get(id)
+        .is_some()
+}
+
+#[test]
+fn first_kill_unlocks_once_with_a_toast() {
+    let mut app = achievement_app(vec![achievement(
+        "first_blood",
+        AchievementCondition::Kills(1),
+    )]);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(0.0, 100.0));
+    spawn_enemy(&mut app, Vec2::new(0.0, 160.0));
+
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+
+    assert!(is_unlocked(&app, "first_blood"));
+    assert_eq!(
+        app.world().resource::<UnlockedAchievements>().unlocks.len(),
+        1
+    );
+    assert_eq!(count::<AchievementToast>(&mut app), 1);
+}
+
+#[test]
+fn perfect_wave_requires_every_shot_to_hit() {
+    let mut app = achievement_app(vec![achievement(
+        "sharpshooter",
+        AchievementCondition::PerfectWave,
+    )]);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(200.0, 100.0));
+
+    // A shot into empty space, then the wave ends
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+    app.world_mut().write_message(WaveCleared);
+    advance(&mut app, 1);
+    assert!(!is_unlocked(&app, "sharpshooter"));
+
+    // The next wave is cleared without a miss
+    spawn_enemy(&mut app, Vec2::new(0.0, 100.0));
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+    app.world_mut().write_message(WaveCleared);
+    advance(&mut app, 1);
+    assert!(is_unlocked(&app, "sharpshooter"));
+}
+
+#[test]
+fn moving_left_spoils_no_retreat() {
+    let mut app = achievement_app(vec![achievement(
+        "no_retreat",
+        AchievementCondition::NoRetreat,
+    )]);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    advance(&mut app, 1);
+
+    app.world_mut()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = -20.0;
+    advance(&mut app, 1);
+    app.world_mut().write_message(WaveCleared);
+    advance(&mut app, 1);
+    assert!(!is_unlocked(&app, "no_retreat"));
+
+    app.world_mut()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = 40.0;
+    advance(&mut app, 1);
+    app.world_mut().write_message(WaveCleared);
+    advance(&mut app, 1);
+    assert!(is_unlocked(&app, "no_retreat"));
+}
+
+#[test]
+fn duplicate_ids_are_rejected() {
+    let list = AchievementList {
+        achievements: vec![
+            achievement("same", AchievementCondition::Victory),
+            achievement("same", AchievementCondition::NoRetreat),
+        ],
+    };
+
+    assert!(matches!(
+        list.validate(3),
+        Err(AchievementListError::DuplicateId(id)) if id == "same"
+    ));
+}
+
+#[test]
+fn waves_beyond_the_campaign_are_rejected() {
+    let list = AchievementList {
+        achievements: vec![achievement("far", AchievementCondition::ReachWave(4))],
+    };
+
+    assert!(list.validate(4).is_ok());
+    assert!(matches!(
+        list.validate(3),
+        Err(AchievementListError::UnreachableWave {
+            wave: 4,
+            waves: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn shipped_achievements_are_valid() {
+    let read = |path: &str| {
+        std::fs::read_to_string(format!("{}/resources/{path}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+    };
+
+    let list: AchievementList = ron::from_str(&read(ACHIEVEMENTS_PATH)).unwrap();
+    let campaign: Campaign = ron::from_str(&read(CAMPAIGN_PATH)).unwrap();
+
+    list.validate(campaign.waves.len()).unwrap();
+    assert!(!list.achievements.is_empty());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::constants::*;
+use vibe_invaders::resources::*;
+
+#[test]
+fn player_bullet_kills_enemy_and_scores() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(0.0, 100.0));
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(count::<Bullet>(&mut app), 1);
+
+    // 350 units at 400 units/s is under a second
+    advance(&mut app, 60);
+
+    assert_eq!(count::<Enemy>(&mut app), 0);
+    assert_eq!(count::<Bullet>(&mut app), 0, "bullet returns to its pool");
+    assert_eq!(app.world().resource::<GameState>().score, ENEMY_POINTS);
+}
+
+#[test]
+fn bullet_resolves_one_hit_when_overlapping_two_enemies() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    // Two enemies close enough that a bullet between them overlaps both
+    spawn_enemy(&mut app, Vec2::new(-ENEMY_SIZE / 2.0 + 2.0, 0.0));
+    spawn_enemy(&mut app, Vec2::new(ENEMY_SIZE / 2.0 - 2.0, 0.0));
+
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+
+    assert_eq!(count::<Enemy>(&mut app), 1);
+    assert_eq!(app.world().resource::<GameState>().score, ENEMY_POINTS);
+}
+
+#[test]
+fn bullet_misses_enemy_in_another_column() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(200.0, 100.0));
+
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 120);
+
+    assert_eq!(count::<Enemy>(&mut app), 1);
+    assert_eq!(
+        count::<Bullet>(&mut app),
+        0,
+        "bullet is released off-screen"
+    );
+    assert_eq!(app.world().resource::<GameState>().score, 0);
+}
+
+#[test]
+fn enemy_fire_costs_a_life_and_clears_the_shots() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, 0.0), Vec2::new(0.0, -BULLET_SPEED));
+    fire_enemy_bullet(&mut app, Vec2::new(200.0, 0.0), Vec2::ZERO);
+    let lives = app.world().resource::<GameState>().lives;
+
+    advance(&mut app, 60);
+
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    assert_eq!(count::<Player>(&mut app), 1);
+    assert_eq!(count::<EnemyBullet>(&mut app), 0);
+    assert_eq!(app.world().resource::<GameState>().lives, lives - 1);
+}
+
+#[test]
+fn enemy_fire_on_the_last_life_ends_the_game() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    last_life(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, 0.0), Vec2::new(0.0, -BULLET_SPEED));
+
+    advance(&mut app, 60);
+
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+    assert_eq!(count::<Player>(&mut app), 0);
+}
+
+#[test]
+fn angled_shots_move_horizontally() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let bullet = fire_enemy_bullet(&mut app, Vec2::ZERO, Vec2::new(100.0, -100.0));
+
+    advance(&mut app, 10);
+
+    let position = app.world().get::<Transform>(bullet).unwrap().translation;
+    assert!(position.x > 0.0 && position.y < 0.0);
+}
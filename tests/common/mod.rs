@@ -0,0 +1,264 @@
+//! Shared helpers for driving gameplay systems through a headless `App`.
+
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::input::touch::{TouchInput, Touches, touch_screen_input_system};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::CursorMoved;
+use vibe_invaders::achievements::AchievementList;
+use vibe_invaders::components::*;
+use vibe_invaders::config::GameConfig;
+use vibe_invaders::constants::*;
+use vibe_invaders::locale::Locale;
+use vibe_invaders::plugins::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::storage::Storage;
+use vibe_invaders::systems::read_pointer;
+use vibe_invaders::waves::*;
+
+/// Fixed frame length used by every test app: one gameplay tick, so every
+/// frame plays exactly one tick
+pub const FRAME: Duration = Duration::from_micros(15_625);
+
+/// Build an app running the game's plugins under `MinimalPlugins`. Rendering,
+/// audio, input and asset loading are left out: the resources and messages
+/// those engine plugins provide are stood in for, and assets use empty handles.
+pub fn game_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        // Input: tests press and release buttons themselves
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<Touches>()
+        .add_message::<TouchInput>()
+        .add_systems(PreUpdate, touch_screen_input_system.before(read_pointer))
+        // Window and UI
+        .init_resource::<UiScale>()
+        .add_message::<CursorMoved>()
+        // Assets
+        .init_resource::<GameTextures>()
+        .init_resource::<GameAudio>()
+        .init_resource::<Assets<GameConfig>>()
+        .init_resource::<Assets<Campaign>>()
+        .init_resource::<Assets<AchievementList>>()
+        .init_resource::<Assets<Locale>>()
+        .add_message::<AssetEvent<GameConfig>>()
+        .add_message::<AssetEvent<AchievementList>>()
+        .add_message::<AssetEvent<Locale>>()
+        .insert_resource(GameConfigHandle(Handle::default()))
+        .insert_resource(CampaignHandle(Handle::default()))
+        .insert_resource(AchievementListHandle(Handle::default()))
+        .add_plugins((GameplayPlugin, InterfacePlugin, PersistencePlugin));
+
+    // Let the pools fill before the first test frame
+    app.update();
+    app
+}
+
+/// Storage in a fresh temporary directory, unique to the test binary and `name`
+pub fn temp_storage(name: &str) -> Storage {
+    let dir = std::env::temp_dir().join(format!(
+        "vibe-invaders-{}-test-{}-{name}",
+        env!("CARGO_CRATE_NAME"),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    Storage::at(dir)
+}
+
+/// Switch to `phase` and run the frame that applies the transition
+pub fn enter(app: &mut App, phase: GamePhase) {
+    app.world_mut()
+        .resource_mut::<NextState<GamePhase>>()
+        .set(phase);
+    app.update();
+}
+
+/// Play a campaign of held waves, so runs start with no enemies and clearing
+/// a wave moves on to the next
+pub fn hold_campaign(app: &mut App) {
+    let campaign = Campaign {
+        waves: vec![held_wave(); 3],
+        fingerprint: 0,
+    };
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Campaign>>()
+        .add(campaign);
+    app.insert_resource(CampaignHandle(handle));
+}
+
+/// Start a run on held waves and remove the player ship, for tests that place
+/// their own entities
+pub fn enter_empty_arena(app: &mut App) {
+    hold_campaign(app);
+    enter(app, GamePhase::Playing);
+
+    let world = app.world_mut();
+    let players: Vec<Entity> = world
+        .query_filtered::<Entity, With<Player>>()
+        .iter(world)
+        .collect();
+    for player in players {
+        world.despawn(player);
+    }
+}
+
+/// A wave with no formation that is never cleared on its own: its enemies
+/// neither move nor fire, and a special spawn that never comes keeps it open
+pub fn held_wave() -> WaveDefinition {
+    WaveDefinition {
+        formation: Vec::new(),
+        speed: SpeedCurve {
+            start: 0.0,
+            end: 0.0,
+        },
+        fire: FirePolicy {
+            interval: f32::INFINITY,
+            shooter: ShooterSelection::Random,
+        },
+        special_spawns: vec![SpecialSpawn {
+            time: f32::INFINITY,
+            kind: EnemyKind::Type1,
+            position: (0.0, 0.0),
+        }],
+    }
+}
+
+/// Run `frames` updates
+pub fn advance(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+/// Hold `key` for a single frame
+pub fn tap(app: &mut App, key: KeyCode) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(key);
+    app.update();
+
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release(key);
+    input.clear();
+}
+
+pub fn current_phase(app: &App) -> GamePhase {
+    *app.world().resource::<State<GamePhase>>().get()
+}
+
+/// Whether the run is running or paused, outside a run `None`
+pub fn run_phase(app: &App) -> Option<RunPhase> {
+    app.world()
+        .get_resource::<State<RunPhase>>()
+        .map(|state| *state.get())
+}
+
+/// Move the selection of the open menu down to `action` and confirm it
+pub fn choose(app: &mut App, action: MenuAction) {
+    for _ in 0..20 {
+        let menu = app
+            .world_mut()
+            .query::<&Menu>()
+            .single(app.world())
+            .unwrap();
+        if menu.selected_action() == action {
+            tap(app, KeyCode::Space);
+            advance(app, 1);
+            return;
+        }
+        tap(app, KeyCode::ArrowDown);
+    }
+    panic!("{action:?} is not in the open menu");
+}
+
+/// Pause the run and choose Suspend & Quit in the pause menu
+pub fn suspend(app: &mut App) {
+    tap(app, KeyCode::Escape);
+    advance(app, 1);
+    choose(app, MenuAction::Suspend);
+}
+
+/// Number of active (not disabled) entities with component `T`
+pub fn count<T: Component>(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<T>>()
+        .iter(app.world())
+        .count()
+}
+
+/// Number of entities with component `T` that are disabled, including pooled ones
+pub fn count_disabled<T: Component>(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), (With<T>, With<Disabled>)>()
+        .iter(app.world())
+        .count()
+}
+
+pub fn spawn_player(app: &mut App, position: Vec2) -> Entity {
+    app.world_mut()
+        .spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Player,
+            Hitbox::new(
+                Vec2::splat(PLAYER_SIZE),
+                CollisionLayer::PLAYER,
+                CollisionLayer::PICKUP,
+            )
+            .with_shrink(PLAYER_HITBOX_SHRINK),
+            DespawnOnExit(GamePhase::Playing),
+        ))
+        .id()
+}
+
+pub fn spawn_enemy(app: &mut App, position: Vec2) -> Entity {
+    app.world_mut()
+        .spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Enemy {
+                original_x: position.x,
+            },
+            EnemyKind::Type1,
+            Hitbox::new(
+                Vec2::splat(ENEMY_SIZE),
+                CollisionLayer::ENEMY,
+                CollisionLayer::NONE,
+            )
+            .with_shrink(ENEMY_HITBOX_SHRINK),
+            DespawnOnExit(GamePhase::Playing),
+        ))
+        .id()
+}
+
+/// Leave the run with one life, so the next hit ends it
+pub fn last_life(app: &mut App) {
+    app.world_mut().resource_mut::<GameState>().lives = 1;
+}
+
+/// Fire an enemy bullet from the pool, the way `enemy_shoot` does
+pub fn fire_enemy_bullet(app: &mut App, position: Vec2, velocity: Vec2) -> Entity {
+    let bullet = app
+        .world_mut()
+        .resource_mut::<EntityPools>()
+        .enemy_bullets
+        .acquire()
+        .expect("enemy bullet pool is empty");
+    app.world_mut()
+        .entity_mut(bullet)
+        .insert((
+            Transform::from_translation(position.extend(0.0)),
+            Velocity {
+                x: velocity.x,
+                y: velocity.y,
+            },
+        ))
+        .remove::<Disabled>();
+    bullet
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use bevy::prelude::*;
+use chrono::{Local, NaiveDate};
+use common::*;
+use rand::RngCore;
+use vibe_invaders::config::GameConfig;
+use vibe_invaders::constants::ENEMY_POINTS;
+use vibe_invaders::daily::*;
+use vibe_invaders::replay::verify;
+use vibe_invaders::resources::*;
+use vibe_invaders::waves::*;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn daily_seed_depends_only_on_the_date() {
+    assert_eq!(daily_seed(date(2026, 3, 14)), daily_seed(date(2026, 3, 14)));
+    assert_ne!(daily_seed(date(2026, 3, 14)), daily_seed(date(2026, 3, 15)));
+}
+
+fn tick(steering: i16, fire: bool) -> TickInput {
+    TickInput {
+        steering,
+        fire,
+        autofire: false,
+    }
+}
+
+#[test]
+fn daily_code_round_trips_and_rejects_edits() {
+    let mut input_log = InputLog::default();
+    input_log.record(tick(-32767, true));
+    input_log.record(tick(0, false));
+    let code = DailyCode::new(date(2026, 3, 14), 420, &input_log);
+
+    let text = code.to_string();
+    assert_eq!(text.parse::<DailyCode>().unwrap(), code);
+
+    let edited = text.replace("-420-", "-9420-");
+    assert!(matches!(
+        edited.parse::<DailyCode>(),
+        Err(DailyCodeError::Checksum)
+    ));
+    assert!("VI-nonsense".parse::<DailyCode>().is_err());
+}
+
+#[test]
+fn input_log_hash_depends_on_every_tick() {
+    let mut fired_early = InputLog::default();
+    fired_early.record(tick(0, true));
+    fired_early.record(tick(0, false));
+
+    let mut fired_late = InputLog::default();
+    fired_late.record(tick(0, false));
+    fired_late.record(tick(0, true));
+
+    assert_eq!(fired_early.ticks(), 2);
+    assert_ne!(fired_early.hash(), fired_late.hash());
+}
+
+#[test]
+fn input_log_keeps_runs_of_identical_ticks() {
+    let mut input_log = InputLog::default();
+    for _ in 0..3 {
+        input_log.record(tick(32767, false));
+    }
+    input_log.record(tick(32767, true));
+
+    assert_eq!(input_log.ticks(), 4);
+    assert_eq!(
+        input_log.inputs().collect::<Vec<_>>(),
+        vec![
+            tick(32767, false),
+            tick(32767, false),
+            tick(32767, false),
+            tick(32767, true)
+        ]
+    );
+}
+
+#[test]
+fn daily_challenge_is_seeded_and_played_once_per_day() {
+    let mut app = game_app();
+    // Pick the daily challenge as the mode, then Play
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+
+    let today = Local::now().date_naive();
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    assert_eq!(*app.world().resource::<RunMode>(), RunMode::Daily(today));
+    let mut expected = GameRng::seeded(daily_seed(today));
+    assert_eq!(
+        app.world_mut().resource_mut::<GameRng>().next_u64(),
+        expected.next_u64()
+    );
+
+    // End the run with a score; it goes to the daily table, not the high scores
+    app.world_mut().resource_mut::<GameState>().score = 50;
+    last_life(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, -200.0), Vec2::new(0.0, -400.0));
+    advance(&mut app, 30);
+
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+    assert!(app.world().resource::<HighScores>().scores.is_empty());
+    let daily_scores = app.world().resource::<DailyScores>();
+    let entry = &daily_scores.scores[0];
+    assert_eq!((entry.date, entry.score), (today, 50));
+    assert!(entry.is_current);
+    let code: DailyCode = entry.code.parse().unwrap();
+    assert_eq!((code.seed, code.score), (daily_seed(today), 50));
+
+    // The mode can no longer be switched to today's challenge
+    enter(&mut app, GamePhase::StartScreen);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(*app.world().resource::<RunMode>(), RunMode::Standard);
+}
+
+/// Press `key` for `frames` frames
+fn hold(app: &mut App, key: KeyCode, frames: usize) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(key);
+    advance(app, frames);
+
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release(key);
+    input.clear();
+}
+
+#[test]
+fn replaying_the_input_log_verifies_the_score() {
+    let mut app = game_app();
+    // Three enemies in a row that fire at the player
+    let mut wave = held_wave();
+    wave.formation = vec!["111".to_string()];
+    wave.fire = FirePolicy {
+        interval: 0.5,
+        shooter: ShooterSelection::Aimed,
+    };
+    wave.special_spawns.clear();
+    let campaign = Campaign {
+        waves: vec![wave],
+        fingerprint: 0,
+    };
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Campaign>>()
+        .add(campaign.clone());
+    app.insert_resource(CampaignHandle(handle));
+    app.world_mut().resource_mut::<GameConfig>().player.lives = 2;
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+
+    // Shoot the middle and right enemies, then wait under the left one
+    tap(&mut app, KeyCode::Space);
+    hold(&mut app, KeyCode::ArrowRight, 13);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 80);
+    hold(&mut app, KeyCode::ArrowLeft, 26);
+    for _ in 0..1000 {
+        if current_phase(&app) != GamePhase::Playing {
+            break;
+        }
+        app.update();
+    }
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+
+    let entry = app.world().resource::<DailyScores>().scores[0].clone();
+    assert_eq!(entry.score, 2 * ENEMY_POINTS);
+    let code: DailyCode = entry.code.parse().unwrap();
+    let config = app.world().resource::<GameConfig>().clone();
+    assert!(verify(&code, &entry.input_log, &config, &campaign).is_ok());
+
+    // A code claiming another score still has a valid checksum, but replaying
+    // the run gives the score away
+    let tampered: DailyCode = DailyCode {
+        score: code.score + 1000,
+        ..code
+    }
+    .to_string()
+    .parse()
+    .unwrap();
+    assert!(matches!(
+        verify(&tampered, &entry.input_log, &config, &campaign),
+        Err(DailyCodeError::Score(score)) if score == code.score
+    ));
+
+    // An input log that was not the one played does not match the code
+    let mut other_log = entry.input_log.clone();
+    other_log.record(TickInput::default());
+    assert!(matches!(
+        verify(&code, &other_log, &config, &campaign),
+        Err(DailyCodeError::InputLog)
+    ));
+}
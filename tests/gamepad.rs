@@ -0,0 +1,187 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::systems::apply_deadzone;
+
+/// Plug in a gamepad; it is assigned on the next frame
+fn connect_gamepad(app: &mut App) -> Entity {
+    let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+    app.update();
+    gamepad
+}
+
+/// Press `button` on `gamepad` for a single frame
+fn press_button(app: &mut App, gamepad: Entity, button: GamepadButton) {
+    app.world_mut()
+        .get_mut::<Gamepad>(gamepad)
+        .unwrap()
+        .digital_mut()
+        .press(button);
+    app.update();
+
+    let mut state = app.world_mut().get_mut::<Gamepad>(gamepad).unwrap();
+    state.digital_mut().release(button);
+    state.digital_mut().clear();
+}
+
+fn set_left_stick_x(app: &mut App, gamepad: Entity, value: f32) {
+    app.world_mut()
+        .get_mut::<Gamepad>(gamepad)
+        .unwrap()
+        .analog_mut()
+        .set(GamepadAxis::LeftStickX, value);
+}
+
+fn player_x(app: &App, player: Entity) -> f32 {
+    app.world().get::<Transform>(player).unwrap().translation.x
+}
+
+#[test]
+fn deadzone_is_removed_and_the_rest_rescaled() {
+    assert_eq!(apply_deadzone(0.15, 0.2), 0.0);
+    assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+    assert_eq!(apply_deadzone(1.0, 0.2), 1.0);
+    assert!((apply_deadzone(-0.6, 0.2) + 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn gamepads_are_assigned_per_player_and_freed_when_unplugged() {
+    let mut app = game_app();
+    let first = connect_gamepad(&mut app);
+    let second = connect_gamepad(&mut app);
+    let assignment = app.world().resource::<GamepadAssignment>();
+    assert_eq!(assignment.players, [Some(first), Some(second)]);
+
+    // Bevy removes `Gamepad` from the entity when the device disconnects
+    app.world_mut().entity_mut(first).remove::<Gamepad>();
+    app.update();
+    assert_eq!(
+        app.world().resource::<GamepadAssignment>().players,
+        [None, Some(second)]
+    );
+
+    let third = connect_gamepad(&mut app);
+    assert_eq!(
+        app.world().resource::<GamepadAssignment>().slot_of(third),
+        Some(0)
+    );
+}
+
+#[test]
+fn left_stick_moves_the_player_outside_the_deadzone() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let gamepad = connect_gamepad(&mut app);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    set_left_stick_x(&mut app, gamepad, 0.1);
+    advance(&mut app, 10);
+    assert_eq!(player_x(&app, player), 0.0);
+
+    set_left_stick_x(&mut app, gamepad, -1.0);
+    advance(&mut app, 10);
+    assert!(player_x(&app, player) < 0.0);
+}
+
+#[test]
+fn dpad_moves_the_player() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let gamepad = connect_gamepad(&mut app);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    app.world_mut()
+        .get_mut::<Gamepad>(gamepad)
+        .unwrap()
+        .analog_mut()
+        .set(GamepadButton::DPadRight, 1.0);
+    advance(&mut app, 10);
+    assert!(player_x(&app, player) > 0.0);
+}
+
+#[test]
+fn face_button_fires_and_start_pauses() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let gamepad = connect_gamepad(&mut app);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    press_button(&mut app, gamepad, GamepadButton::South);
+    assert_eq!(count::<Bullet>(&mut app), 1);
+
+    press_button(&mut app, gamepad, GamepadButton::Start);
+    advance(&mut app, 1);
+    assert_eq!(run_phase(&app), Some(RunPhase::Paused));
+
+    // The run stands still while paused
+    let bullet_y = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<Bullet>>()
+            .single(app.world())
+            .unwrap()
+            .translation
+            .y
+    };
+    let before = bullet_y(&mut app);
+    app.world_mut()
+        .get_mut::<Gamepad>(gamepad)
+        .unwrap()
+        .analog_mut()
+        .set(GamepadButton::DPadRight, 1.0);
+    advance(&mut app, 10);
+    assert_eq!(bullet_y(&mut app), before);
+    assert_eq!(player_x(&app, player), 0.0);
+    app.world_mut()
+        .get_mut::<Gamepad>(gamepad)
+        .unwrap()
+        .analog_mut()
+        .set(GamepadButton::DPadRight, 0.0);
+
+    press_button(&mut app, gamepad, GamepadButton::Start);
+    advance(&mut app, 1);
+    assert_eq!(run_phase(&app), Some(RunPhase::Running));
+    advance(&mut app, 1);
+    assert!(bullet_y(&mut app) > before);
+
+    // Suspend & Quit from the pause menu, then continue from the main menu
+    press_button(&mut app, gamepad, GamepadButton::Start);
+    advance(&mut app, 1);
+    press_button(&mut app, gamepad, GamepadButton::DPadDown);
+    press_button(&mut app, gamepad, GamepadButton::South);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+
+    press_button(&mut app, gamepad, GamepadButton::South);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+}
+
+#[test]
+fn gamepad_alone_enters_initials_after_a_qualifying_run() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let gamepad = connect_gamepad(&mut app);
+    app.world_mut().resource_mut::<GameState>().score = 50;
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    last_life(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, -200.0), Vec2::new(0.0, -400.0));
+    advance(&mut app, 30);
+    assert_eq!(current_phase(&app), GamePhase::NameEntry);
+
+    press_button(&mut app, gamepad, GamepadButton::DPadUp);
+    press_button(&mut app, gamepad, GamepadButton::DPadRight);
+    press_button(&mut app, gamepad, GamepadButton::DPadDown);
+    press_button(&mut app, gamepad, GamepadButton::DPadRight);
+    press_button(&mut app, gamepad, GamepadButton::DPadLeft);
+    press_button(&mut app, gamepad, GamepadButton::DPadUp);
+    press_button(&mut app, gamepad, GamepadButton::South);
+    advance(&mut app, 1);
+
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+    let entry = &app.world().resource::<HighScores>().get_top_scores()[0];
+    assert_eq!(entry.name, "BAA");
+    assert_eq!(entry.score, 50);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::constants::*;
+use vibe_invaders::messages::*;
+use vibe_invaders::resources::*;
+
+/// Start a run with a player ship and the HUD
+fn playing_with_hud() -> App {
+    let mut app = game_app();
+    hold_campaign(&mut app);
+    enter(&mut app, GamePhase::Playing);
+    app.update();
+    app
+}
+
+fn text<T: Component>(app: &mut App) -> String {
+    let mut query = app.world_mut().query_filtered::<&Text, With<T>>();
+    query.single(app.world()).unwrap().0.clone()
+}
+
+fn kill_enemy(app: &mut App) {
+    app.world_mut().write_message(EnemyKilled {
+        position: Vec3::ZERO,
+        points: ENEMY_POINTS,
+        kind: EnemyKind::Type1,
+        saucer: false,
+    });
+    app.update();
+}
+
+#[test]
+fn hud_shows_score_high_score_wave_and_status() {
+    let mut app = playing_with_hud();
+    assert_eq!(text::<Score>(&mut app), "Score: 0");
+    assert_eq!(text::<WaveText>(&mut app), "Wave 1");
+    assert_eq!(text::<StatusText>(&mut app), "");
+
+    app.world_mut().resource_mut::<HighScores>().add_score(50);
+    app.world_mut().resource_mut::<GameState>().score = 80;
+    app.world_mut().resource_mut::<CurrentWave>().index = 2;
+    app.world_mut()
+        .resource_mut::<ActiveEffects>()
+        .effects
+        .push(ActiveEffect {
+            name: "menu.autofire".to_string(),
+            remaining: 4.5,
+        });
+    app.update();
+
+    assert_eq!(text::<Score>(&mut app), "Score: 80");
+    assert_eq!(text::<HighScoreText>(&mut app), "Hi-Score: 80");
+    assert_eq!(text::<WaveText>(&mut app), "Wave 3");
+    assert_eq!(text::<StatusText>(&mut app), "Autofire 5s");
+
+    // The effect wears off
+    advance(&mut app, 300);
+    assert_eq!(text::<StatusText>(&mut app), "");
+}
+
+#[test]
+fn ship_icons_follow_the_lives_left() {
+    let mut app = playing_with_hud();
+    assert_eq!(count::<LifeIcon>(&mut app), PLAYER_LIVES as usize);
+
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, -200.0), Vec2::new(0.0, -400.0));
+    advance(&mut app, 30);
+    assert_eq!(count::<LifeIcon>(&mut app), PLAYER_LIVES as usize - 1);
+
+    app.world_mut().resource_mut::<GameState>().lives = 5;
+    app.update();
+    assert_eq!(count::<LifeIcon>(&mut app), 5);
+}
+
+#[test]
+fn combo_chains_kills_and_runs_out() {
+    let mut app = playing_with_hud();
+
+    kill_enemy(&mut app);
+    assert_eq!(text::<ComboText>(&mut app), "");
+    kill_enemy(&mut app);
+    kill_enemy(&mut app);
+    assert_eq!(app.world().resource::<Combo>().count, 3);
+    assert_eq!(text::<ComboText>(&mut app), "Combo x3");
+
+    // Two seconds without a kill end the chain
+    advance(&mut app, 130);
+    assert_eq!(app.world().resource::<Combo>().count, 0);
+    assert_eq!(text::<ComboText>(&mut app), "");
+}
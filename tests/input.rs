@@ -0,0 +1,270 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::input::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::settings::*;
+use vibe_invaders::storage::*;
+
+#[test]
+fn bindings_are_saved_by_name() {
+    let bindings = [
+        Binding::Key(KeyCode::KeyZ),
+        Binding::Mouse(MouseButton::Right),
+        Binding::Gamepad(GamepadButton::RightTrigger2),
+        Binding::Stick {
+            axis: GamepadAxis::LeftStickY,
+            positive: false,
+        },
+    ];
+    let names: Vec<String> = bindings
+        .iter()
+        .map(|binding| binding.name().unwrap())
+        .collect();
+
+    assert_eq!(
+        names,
+        [
+            "KeyZ",
+            "Mouse:Right",
+            "Pad:RightTrigger2",
+            "Pad:LeftStickY-"
+        ]
+    );
+    for (binding, name) in bindings.iter().zip(&names) {
+        assert_eq!(Binding::from_name(name), Some(*binding));
+    }
+    assert_eq!(Binding::from_name("Pad:Nothing"), None);
+}
+
+#[test]
+fn key_only_bindings_from_older_saves_still_load() {
+    let storage = temp_storage("older-save");
+    storage
+        .write_raw(
+            Settings::KEY,
+            "(version: 1, data: (music_volume: 0.5, key_bindings: (fire: [\"KeyZ\"])))",
+        )
+        .unwrap();
+
+    let loaded: Settings = storage.load();
+
+    assert_eq!(loaded.music_volume, 0.5);
+    assert_eq!(loaded.bindings.fire, [Binding::Key(KeyCode::KeyZ)]);
+    assert_eq!(loaded.bindings.pause, ActionBindings::default().pause);
+}
+
+#[test]
+fn bindings_conflict_only_within_the_same_context() {
+    let mut bindings = ActionBindings::default();
+
+    // Space fires during play and confirms in menus
+    assert_eq!(
+        bindings.bind(Action::Fire, Binding::Key(KeyCode::KeyA)),
+        Err(BindError::Conflict(Action::MoveLeft))
+    );
+    assert_eq!(
+        bindings.bind(Action::Fire, Binding::Key(KeyCode::Space)),
+        Err(BindError::AlreadyBound)
+    );
+    assert_eq!(
+        bindings.bind(Action::Confirm, Binding::Key(KeyCode::Enter)),
+        Ok(())
+    );
+    assert_eq!(
+        bindings.bind(Action::Back, Binding::Key(KeyCode::Enter)),
+        Err(BindError::Conflict(Action::Confirm))
+    );
+    assert_eq!(
+        bindings.bind(Action::Fire, Binding::Key(KeyCode::F1)),
+        Err(BindError::Unbindable)
+    );
+}
+
+#[test]
+fn the_last_binding_of_an_action_cannot_be_removed() {
+    let mut bindings = ActionBindings {
+        confirm: vec![Binding::Key(KeyCode::Space)],
+        ..default()
+    };
+
+    assert_eq!(bindings.unbind_last(Action::Confirm), None);
+    assert_eq!(bindings.confirm, [Binding::Key(KeyCode::Space)]);
+}
+
+#[test]
+fn controls_screen_adds_a_binding_and_rejects_conflicts() {
+    let mut app = game_app();
+    choose(&mut app, MenuAction::Controls);
+    assert_eq!(current_phase(&app), GamePhase::Controls);
+
+    // Move Left is selected first; move down to Fire and bind Z
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::KeyZ);
+    let settings = app.world().resource::<Settings>();
+    assert!(
+        settings
+            .bindings
+            .fire
+            .contains(&Binding::Key(KeyCode::KeyZ))
+    );
+
+    // D already moves right
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::KeyD);
+    let settings = app.world().resource::<Settings>();
+    assert!(
+        !settings
+            .bindings
+            .fire
+            .contains(&Binding::Key(KeyCode::KeyD))
+    );
+    let menu = app.world().resource::<RebindingMenu>();
+    assert!(menu.status.contains("Move Right"), "{}", menu.status);
+
+    // Left removes the input added last
+    tap(&mut app, KeyCode::ArrowLeft);
+    let settings = app.world().resource::<Settings>();
+    assert!(
+        !settings
+            .bindings
+            .fire
+            .contains(&Binding::Key(KeyCode::KeyZ))
+    );
+
+    tap(&mut app, KeyCode::Escape);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+}
+
+#[test]
+fn controls_screen_leaves_fullscreen_shortcuts_alone() {
+    let mut app = game_app();
+    choose(&mut app, MenuAction::Controls);
+    app.world_mut().resource_mut::<Settings>().bindings.confirm =
+        vec![Binding::Key(KeyCode::Enter)];
+
+    // Alt+Enter toggles fullscreen without starting a capture, even when Enter
+    // confirms
+    press_with_alt(&mut app, KeyCode::Enter);
+    assert!(!app.world().resource::<RebindingMenu>().capturing);
+    assert_eq!(
+        app.world().resource::<Settings>().display_mode,
+        DisplayMode::Borderless
+    );
+
+    // While capturing, F11 and Alt+Enter toggle the mode back and forth but
+    // are not bound
+    tap(&mut app, KeyCode::Enter);
+    tap(&mut app, KeyCode::F11);
+    press_with_alt(&mut app, KeyCode::Enter);
+    assert!(app.world().resource::<RebindingMenu>().capturing);
+    assert_eq!(
+        app.world().resource::<Settings>().display_mode,
+        DisplayMode::Borderless
+    );
+
+    // Alt on its own is bound when released
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.press(KeyCode::AltLeft);
+    app.update();
+    assert!(app.world().resource::<RebindingMenu>().capturing);
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.clear();
+    input.release(KeyCode::AltLeft);
+    app.update();
+    assert!(!app.world().resource::<RebindingMenu>().capturing);
+    let bindings = &app.world().resource::<Settings>().bindings;
+    assert!(bindings.move_left.contains(&Binding::Key(KeyCode::AltLeft)));
+}
+
+/// Hold left Alt, tap `key`, then release Alt
+fn press_with_alt(app: &mut App, key: KeyCode) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::AltLeft);
+    app.update();
+    tap(app, key);
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release(KeyCode::AltLeft);
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+}
+
+#[test]
+fn mouse_button_bound_to_fire_shoots() {
+    let mut app = game_app();
+    app.world_mut()
+        .resource_mut::<Settings>()
+        .bindings
+        .fire
+        .push(Binding::Mouse(MouseButton::Left));
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    app.update();
+
+    assert_eq!(count::<Bullet>(&mut app), 1);
+}
+
+/// Bullets fired while Fire is held for one second through `hold`
+fn shots_while_held(autofire: bool, hold: impl FnOnce(&mut App)) -> usize {
+    let mut app = game_app();
+    app.world_mut().resource_mut::<Settings>().autofire = autofire;
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    hold(&mut app);
+    advance(&mut app, 62);
+    count::<Bullet>(&mut app)
+}
+
+fn hold_space(app: &mut App) {
+    let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keyboard.press(KeyCode::Space);
+    app.update();
+    // Still held, but no longer just pressed
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+}
+
+fn hold_fire_button(app: &mut App) {
+    app.world_mut().spawn((
+        TapButton(TapTarget::Action(Action::Fire)),
+        Interaction::Pressed,
+    ));
+    app.update();
+}
+
+#[test]
+fn holding_fire_repeats_only_with_autofire() {
+    // One shot on the press, then one every 0.25 s
+    assert_eq!(shots_while_held(false, hold_space), 1);
+    assert_eq!(shots_while_held(true, hold_space), 4);
+    assert_eq!(shots_while_held(true, hold_fire_button), 4);
+}
+
+#[test]
+fn controls_screen_toggles_autofire() {
+    let mut app = game_app();
+    choose(&mut app, MenuAction::Controls);
+
+    // The autofire row follows the actions
+    for _ in 0..Action::ALL.len() {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    assert!(app.world().resource::<Settings>().autofire);
+    tap(&mut app, KeyCode::ArrowRight);
+    assert!(!app.world().resource::<Settings>().autofire);
+}
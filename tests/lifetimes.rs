@@ -0,0 +1,122 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::resources::*;
+
+#[test]
+fn leaving_playing_despawns_phase_entities() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(0.0, 100.0));
+    spawn_enemy(&mut app, Vec2::new(60.0, 100.0));
+
+    enter(&mut app, GamePhase::GameOver);
+
+    assert_eq!(count::<Player>(&mut app), 0);
+    assert_eq!(count::<Enemy>(&mut app), 0);
+}
+
+#[test]
+fn leaving_playing_returns_bullets_to_pool() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let pooled = count_disabled::<EnemyBullet>(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::ZERO, Vec2::ZERO);
+    fire_enemy_bullet(&mut app, Vec2::new(50.0, 0.0), Vec2::ZERO);
+    app.update();
+    assert_eq!(count::<EnemyBullet>(&mut app), 2);
+
+    enter(&mut app, GamePhase::GameOver);
+
+    assert_eq!(count::<EnemyBullet>(&mut app), 0);
+    assert_eq!(count_disabled::<EnemyBullet>(&mut app), pooled);
+}
+
+#[test]
+fn game_over_screen_is_removed_on_restart() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    app.world_mut().resource_mut::<GameState>().score = 120;
+    enter(&mut app, GamePhase::GameOver);
+    assert!(count::<Text>(&mut app) > 0);
+
+    enter_empty_arena(&mut app);
+
+    let mut query = app
+        .world_mut()
+        .query_filtered::<&DespawnOnExit<GamePhase>, With<Text>>();
+    let game_over_texts = query
+        .iter(app.world())
+        .filter(|scope| scope.0 == GamePhase::GameOver)
+        .count();
+    assert_eq!(game_over_texts, 0);
+    assert_eq!(app.world().resource::<GameState>().score, 0);
+}
+
+#[test]
+fn lifetime_despawns_entity_when_expired() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let entity = app
+        .world_mut()
+        .spawn(Lifetime::new(FRAME.as_secs_f32() * 5.0))
+        .id();
+
+    advance(&mut app, 3);
+    assert!(app.world().get_entity(entity).is_ok());
+
+    advance(&mut app, 5);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn offscreen_entities_are_despawned() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let height = app.world().resource::<WindowDimensions>().height;
+    let inside = app
+        .world_mut()
+        .spawn((Transform::default(), OffscreenDespawn { margin: 10.0 }))
+        .id();
+    let outside = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, height, 0.0),
+            OffscreenDespawn { margin: 10.0 },
+        ))
+        .id();
+
+    app.update();
+
+    assert!(app.world().get_entity(inside).is_ok());
+    assert!(app.world().get_entity(outside).is_err());
+}
+
+#[test]
+fn lifetimes_stand_still_while_paused_but_toasts_fade() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    let particle = app
+        .world_mut()
+        .spawn(Lifetime::new(FRAME.as_secs_f32() * 5.0))
+        .id();
+    let toast = app
+        .world_mut()
+        .spawn((
+            AchievementToast::default(),
+            TextColor(Color::WHITE),
+            BackgroundColor(Color::BLACK),
+        ))
+        .id();
+
+    tap(&mut app, KeyCode::Escape);
+    advance(&mut app, 1);
+    assert_eq!(run_phase(&app), Some(RunPhase::Paused));
+    advance(&mut app, 250);
+
+    assert!(app.world().get_entity(particle).is_ok());
+    assert!(app.world().get_entity(toast).is_err());
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::input::*;
+use vibe_invaders::locale::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::settings::Settings;
+
+fn shipped_locale(language: &Language) -> Locale {
+    let bytes = std::fs::read(format!(
+        "{}/resources/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        language.path()
+    ))
+    .unwrap();
+    Locale::parse(&bytes).unwrap()
+}
+
+/// The `{name}` placeholders of a string, sorted
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = text
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn translations_have_every_english_string() {
+    let english = Locale::english();
+    for language in &LANGUAGES {
+        let locale = shipped_locale(language);
+        for (key, text) in &english.strings {
+            let translated = locale
+                .strings
+                .get(key)
+                .unwrap_or_else(|| panic!("{} lacks '{key}'", language.code));
+            assert_eq!(
+                placeholders(translated),
+                placeholders(text),
+                "{} '{key}'",
+                language.code
+            );
+        }
+        for key in english.plurals.keys() {
+            assert!(
+                locale.plurals.contains_key(key),
+                "{} lacks '{key}'",
+                language.code
+            );
+        }
+    }
+}
+
+#[test]
+fn plural_rules_pick_the_form_for_a_count() {
+    use PluralCategory::*;
+
+    let forms = |rule: PluralRule| [0, 1, 2, 5, 11, 21, 22, 112].map(|n| rule.category(n));
+    assert_eq!(
+        forms(PluralRule::OneOther),
+        [Other, One, Other, Other, Other, Other, Other, Other]
+    );
+    assert_eq!(
+        forms(PluralRule::ZeroOneOther),
+        [One, One, Other, Other, Other, Other, Other, Other]
+    );
+    assert_eq!(forms(PluralRule::Invariant), [Other; 8]);
+    assert_eq!(
+        forms(PluralRule::EastSlavic),
+        [Many, One, Few, Many, Many, One, Few, Many]
+    );
+}
+
+#[test]
+fn strings_numbers_and_plurals_follow_the_language() {
+    let mut localization = Localization::default();
+    assert_eq!(localization.number(1_234_567), "1,234,567");
+    assert_eq!(localization.number(999), "999");
+    assert_eq!(
+        localization.plural("stats.games_played", 1, &[]),
+        "1 game played"
+    );
+    assert_eq!(
+        localization.format("hud.score", &[("score", &localization.number(2500))]),
+        "Score: 2,500"
+    );
+
+    localization.set("de", shipped_locale(&LANGUAGES[1]));
+    assert_eq!(localization.number(1_234_567), "1.234.567");
+    assert_eq!(
+        localization.plural("stats.games_played", 1000, &[]),
+        "1.000 Spiele gespielt"
+    );
+    assert_eq!(localization.percent(0.5), "50 %");
+
+    // Strings a translation lacks are shown in English
+    localization.set("xx", Locale::default());
+    assert_eq!(localization.text("menu.play"), "Play");
+    assert_eq!(localization.text("no.such.key"), "no.such.key");
+}
+
+#[test]
+fn russian_counts_with_east_slavic_plurals_in_its_own_font() {
+    let mut localization = Localization::default();
+    localization.set("ru", shipped_locale(Language::from_code("ru").unwrap()));
+
+    assert_eq!(localization.number(1_234_567), "1 234 567");
+    assert_eq!(
+        localization.plural("stats.games_played", 21, &[]),
+        "21 игра сыграна"
+    );
+    assert_eq!(
+        localization.plural("stats.games_played", 3, &[]),
+        "3 игры сыграно"
+    );
+    assert_eq!(
+        localization.plural("stats.games_played", 11, &[]),
+        "11 игр сыграно"
+    );
+    assert_eq!(
+        localization.fonts().next(),
+        Some("fonts/DejaVuSansMono.ttf")
+    );
+}
+
+#[test]
+fn every_font_of_the_chain_is_shipped() {
+    let english = Locale::english();
+    let locales = LANGUAGES.iter().map(shipped_locale);
+    for font in locales.flat_map(|locale| locale.fonts).chain(english.fonts) {
+        let path = format!("{}/resources/{font}", env!("CARGO_MANIFEST_DIR"));
+        assert!(std::path::Path::new(&path).is_file(), "{font} is missing");
+    }
+}
+
+#[test]
+fn input_labels_follow_the_language() {
+    let mut localization = Localization::default();
+    let left_stick_left = Binding::Stick {
+        axis: GamepadAxis::LeftStickX,
+        positive: false,
+    };
+    assert_eq!(Binding::Key(KeyCode::KeyA).label(&localization), "A");
+    assert_eq!(Binding::Key(KeyCode::ArrowUp).label(&localization), "Up");
+    assert_eq!(
+        Binding::Mouse(MouseButton::Left).label(&localization),
+        "Mouse Left"
+    );
+    assert_eq!(
+        Binding::Gamepad(GamepadButton::South).label(&localization),
+        "Pad A"
+    );
+    assert_eq!(left_stick_left.label(&localization), "Left Stick Left");
+
+    localization.set("de", shipped_locale(&LANGUAGES[1]));
+    assert_eq!(Binding::Key(KeyCode::KeyA).label(&localization), "A");
+    assert_eq!(
+        Binding::Key(KeyCode::Space).label(&localization),
+        "Leertaste"
+    );
+    assert_eq!(left_stick_left.label(&localization), "Linker Stick links");
+}
+
+#[test]
+fn picking_a_language_relabels_the_settings_screen() {
+    let mut app = game_app();
+    let german = app
+        .world_mut()
+        .resource_mut::<Assets<Locale>>()
+        .add(shipped_locale(&LANGUAGES[1]));
+    app.insert_resource(LocaleHandles(vec![
+        Handle::default(),
+        german,
+        Handle::default(),
+    ]));
+    enter(&mut app, GamePhase::Settings);
+
+    // The language item is second to last, just above Back
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::ArrowRight);
+    advance(&mut app, 1);
+
+    assert_eq!(app.world().resource::<Settings>().language, "de");
+    assert_eq!(app.world().resource::<Localization>().language, "de");
+    let labels: Vec<String> = app
+        .world_mut()
+        .query::<(&MenuItem, &Text)>()
+        .iter(app.world())
+        .map(|(_, text)| text.0.clone())
+        .collect();
+    assert!(labels.contains(&"> Sprache: < Deutsch > <".to_string()));
+    let title = app
+        .world_mut()
+        .query::<(&Localized, &Text)>()
+        .iter(app.world())
+        .find(|(localized, _)| localized.0 == "settings.title")
+        .map(|(_, text)| text.0.clone());
+    assert_eq!(title.as_deref(), Some("EINSTELLUNGEN"));
+}
+
+fn hint_text(app: &mut App, key: &str) -> Option<String> {
+    app.world_mut()
+        .query::<(&BindingHint, &Text)>()
+        .iter(app.world())
+        .find(|(hint, _)| hint.0 == key)
+        .map(|(_, text)| text.0.clone())
+}
+
+#[test]
+fn hints_name_the_current_bindings() {
+    let mut app = game_app();
+    let hint = hint_text(&mut app, "start.hint").unwrap();
+    assert!(hint.contains("Space, Pad A - Confirm"));
+
+    app.world_mut()
+        .resource_mut::<Settings>()
+        .bindings
+        .bind(Action::Confirm, Binding::Key(KeyCode::Enter))
+        .unwrap();
+    app.update();
+
+    let hint = hint_text(&mut app, "start.hint").unwrap();
+    assert!(hint.contains("Space, Pad A, Enter - Confirm"));
+    assert!(hint.starts_with("Up, D-Pad Up / Down, D-Pad Down - Select"));
+}
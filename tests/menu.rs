@@ -0,0 +1,165 @@
+mod common;
+
+use bevy::prelude::*;
+use chrono::Local;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::daily::*;
+use vibe_invaders::input::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::settings::Settings;
+
+fn selected_action(app: &mut App) -> MenuAction {
+    app.world_mut()
+        .query::<&Menu>()
+        .single(app.world())
+        .unwrap()
+        .selected_action()
+}
+
+/// Label of every item of the open menu, in order
+fn item_labels(app: &mut App) -> Vec<String> {
+    let mut items: Vec<(usize, String)> = app
+        .world_mut()
+        .query::<(&MenuItem, &Text)>()
+        .iter(app.world())
+        .map(|(item, text)| (item.0, text.0.clone()))
+        .collect();
+    items.sort();
+    items.into_iter().map(|(_, label)| label).collect()
+}
+
+#[test]
+fn arrow_keys_move_the_selection_and_change_items() {
+    let mut app = game_app();
+    assert_eq!(selected_action(&mut app), MenuAction::Play);
+    assert_eq!(item_labels(&mut app)[0], "> Play <");
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowDown);
+    assert_eq!(selected_action(&mut app), MenuAction::Difficulty);
+
+    tap(&mut app, KeyCode::ArrowRight);
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Hard);
+    assert_eq!(item_labels(&mut app)[2], "> Difficulty: < Hard > <");
+    tap(&mut app, KeyCode::ArrowRight);
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Easy);
+
+    // The selection wraps around to the last item
+    for _ in 0..3 {
+        tap(&mut app, KeyCode::ArrowUp);
+    }
+    assert_eq!(selected_action(&mut app), MenuAction::Quit);
+    tap(&mut app, KeyCode::Space);
+    assert!(app.should_exit().is_some());
+}
+
+#[test]
+fn menus_follow_the_navigation_bindings() {
+    let mut app = game_app();
+    app.world_mut()
+        .resource_mut::<Settings>()
+        .bindings
+        .menu_down
+        .push(Binding::Key(KeyCode::KeyS));
+
+    tap(&mut app, KeyCode::KeyS);
+    assert_eq!(selected_action(&mut app), MenuAction::Mode);
+    tap(&mut app, KeyCode::ArrowDown);
+    assert_eq!(selected_action(&mut app), MenuAction::Difficulty);
+
+    // Items are only reached through the menu, not by letter shortcuts
+    tap(&mut app, KeyCode::KeyK);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+}
+
+#[test]
+fn mode_item_picks_the_daily_challenge() {
+    let mut app = game_app();
+    app.world_mut().insert_resource(Difficulty::Hard);
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    let today = Local::now().date_naive();
+    assert_eq!(*app.world().resource::<RunMode>(), RunMode::Daily(today));
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Normal);
+
+    // The daily challenge keeps its difficulty
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowRight);
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Normal);
+
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    assert!(app.world().resource::<DailyScores>().attempted(today));
+}
+
+#[test]
+fn settings_screen_changes_settings() {
+    let mut app = game_app();
+    for _ in 0..3 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::Settings);
+    assert_eq!(selected_action(&mut app), MenuAction::MasterVolume);
+
+    tap(&mut app, KeyCode::ArrowLeft);
+    tap(&mut app, KeyCode::ArrowLeft);
+    let settings = app.world().resource::<Settings>();
+    assert!((settings.master_volume - 0.8).abs() < 1e-6);
+
+    // Confirming a toggle flips it
+    for _ in 0..7 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    assert_eq!(selected_action(&mut app), MenuAction::Autofire);
+    tap(&mut app, KeyCode::Space);
+    assert!(app.world().resource::<Settings>().autofire);
+
+    tap(&mut app, KeyCode::Escape);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+}
+
+#[test]
+fn clicking_an_item_confirms_it() {
+    let mut app = game_app();
+    let high_scores = app
+        .world_mut()
+        .query::<(Entity, &MenuItem)>()
+        .iter(app.world())
+        .find(|(_, item)| item.0 == 4)
+        .unwrap()
+        .0;
+    app.world_mut()
+        .entity_mut(high_scores)
+        .insert(Interaction::Pressed);
+    advance(&mut app, 2);
+    assert_eq!(current_phase(&app), GamePhase::HighScores);
+
+    tap(&mut app, KeyCode::Escape);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+}
+
+#[test]
+fn game_over_menu_returns_to_the_main_menu() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    enter(&mut app, GamePhase::GameOver);
+    assert_eq!(selected_action(&mut app), MenuAction::Restart);
+
+    let delay = app.world().resource::<GameOverTimer>().required_delay;
+    advance(&mut app, (delay / FRAME.as_secs_f32()).ceil() as usize);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+    assert_eq!(selected_action(&mut app), MenuAction::Play);
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+use bevy::window::{CursorMoved, PrimaryWindow, WindowResolution};
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::input::*;
+use vibe_invaders::resources::*;
+
+/// A primary window the size of the playfield
+fn spawn_window(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((
+            Window {
+                resolution: WindowResolution::new(800, 600),
+                ..default()
+            },
+            PrimaryWindow,
+        ))
+        .id()
+}
+
+fn touch(app: &mut App, window: Entity, id: u64, phase: TouchPhase, position: Vec2) {
+    app.world_mut().write_message(TouchInput {
+        phase,
+        position,
+        window,
+        force: None,
+        id,
+    });
+    app.update();
+}
+
+fn player_x(app: &App, player: Entity) -> f32 {
+    app.world().get::<Transform>(player).unwrap().translation.x
+}
+
+fn pointer_mode(app: &App) -> PointerMode {
+    app.world().resource::<PointerInput>().mode
+}
+
+#[test]
+fn ship_follows_the_cursor_and_a_click_fires() {
+    let mut app = game_app();
+    let window = spawn_window(&mut app);
+    enter_empty_arena(&mut app);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    app.world_mut().write_message(CursorMoved {
+        window,
+        position: Vec2::new(600.0, 300.0),
+        delta: None,
+    });
+    advance(&mut app, 60);
+    assert_eq!(pointer_mode(&app), PointerMode::Mouse);
+    assert!((player_x(&app, player) - 200.0).abs() < 0.01);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    app.update();
+    assert_eq!(count::<Bullet>(&mut app), 1);
+
+    // Keys take over until the mouse moves again
+    tap(&mut app, KeyCode::ArrowLeft);
+    assert_eq!(pointer_mode(&app), PointerMode::Off);
+    assert_eq!(app.world().resource::<PointerInput>().target_x, None);
+}
+
+#[test]
+fn first_touch_turns_on_touch_mode_and_a_drag_moves_the_ship() {
+    let mut app = game_app();
+    let window = spawn_window(&mut app);
+    enter_empty_arena(&mut app);
+    let player = spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    assert_eq!(pointer_mode(&app), PointerMode::Off);
+
+    touch(
+        &mut app,
+        window,
+        1,
+        TouchPhase::Started,
+        Vec2::new(200.0, 100.0),
+    );
+    advance(&mut app, 60);
+    assert_eq!(pointer_mode(&app), PointerMode::Touch);
+    assert!((player_x(&app, player) + 200.0).abs() < 0.01);
+
+    // Touches on the button bar do not steer
+    touch(
+        &mut app,
+        window,
+        1,
+        TouchPhase::Ended,
+        Vec2::new(200.0, 100.0),
+    );
+    touch(
+        &mut app,
+        window,
+        2,
+        TouchPhase::Started,
+        Vec2::new(700.0, 560.0),
+    );
+    advance(&mut app, 30);
+    assert!((player_x(&app, player) + 200.0).abs() < 0.01);
+    assert_eq!(pointer_mode(&app), PointerMode::Touch);
+}
+
+#[test]
+fn on_screen_buttons_open_menus_and_fire() {
+    let mut app = game_app();
+    // Confirm plays, the first item of the main menu
+    let shortcut = app
+        .world_mut()
+        .spawn((
+            TapButton(TapTarget::Action(Action::Confirm)),
+            Interaction::None,
+        ))
+        .id();
+    app.world_mut()
+        .entity_mut(shortcut)
+        .insert(Interaction::Pressed);
+    advance(&mut app, 2);
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    app.world_mut().entity_mut(shortcut).despawn();
+
+    app.world_mut().spawn((
+        TapButton(TapTarget::Action(Action::Fire)),
+        Interaction::Pressed,
+    ));
+    app.update();
+    assert_eq!(count::<Bullet>(&mut app), 1);
+
+    // Holding the button does not fire again
+    advance(&mut app, 5);
+    assert_eq!(count::<Bullet>(&mut app), 1);
+}
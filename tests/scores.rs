@@ -0,0 +1,187 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::config::*;
+use vibe_invaders::constants::*;
+use vibe_invaders::resources::*;
+
+#[test]
+fn high_scores_keep_top_five_sorted() {
+    let mut high_scores = HighScores::default();
+    for score in [30, 10, 70, 50, 20, 60, 40] {
+        high_scores.add_score(score);
+    }
+
+    let scores: Vec<u32> = high_scores
+        .get_top_scores()
+        .iter()
+        .map(|entry| entry.score)
+        .collect();
+    assert_eq!(scores, [70, 60, 50, 40, 30]);
+}
+
+#[test]
+fn only_latest_score_is_current() {
+    let mut high_scores = HighScores::default();
+    high_scores.add_score(50);
+    high_scores.add_score(20);
+
+    let current: Vec<u32> = high_scores
+        .get_top_scores()
+        .iter()
+        .filter(|entry| entry.is_current)
+        .map(|entry| entry.score)
+        .collect();
+    assert_eq!(current, [20]);
+}
+
+#[test]
+fn game_over_timer_requires_delay() {
+    let mut timer = GameOverTimer::default();
+    assert!(!timer.can_restart());
+
+    timer.elapsed = timer.required_delay;
+    assert!(timer.can_restart());
+
+    timer.reset();
+    assert!(!timer.can_restart());
+}
+
+#[test]
+fn game_state_reset_clears_every_timer() {
+    let mut game_state = GameState {
+        score: 120,
+        enemy_direction: -1.0,
+        enemy_shoot_timer: 0.7,
+        player_fire_timer: 0.3,
+        star_spawn_timer: 0.2,
+        victory: true,
+        lives: 1,
+    };
+    game_state.reset(3);
+
+    assert_eq!(game_state.score, 0);
+    assert_eq!(game_state.enemy_direction, 1.0);
+    assert_eq!(game_state.enemy_shoot_timer, 0.0);
+    assert_eq!(game_state.player_fire_timer, 0.0);
+    assert_eq!(game_state.star_spawn_timer, 0.0);
+    assert!(!game_state.victory);
+    assert_eq!(game_state.lives, 3);
+}
+
+#[test]
+fn restart_is_ignored_until_delay_has_passed() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    enter(&mut app, GamePhase::GameOver);
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+
+    let delay = app.world().resource::<GameOverTimer>().required_delay;
+    let frames = (delay / FRAME.as_secs_f32()).ceil() as usize;
+    advance(&mut app, frames);
+
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+}
+
+#[test]
+fn qualifying_run_records_initials() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    app.world_mut().resource_mut::<GameState>().score = 50;
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    last_life(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, -200.0), Vec2::new(0.0, -400.0));
+    advance(&mut app, 30);
+    assert_eq!(current_phase(&app), GamePhase::NameEntry);
+
+    tap(&mut app, KeyCode::KeyB);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::ArrowRight);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Enter);
+    advance(&mut app, 1);
+
+    assert_eq!(current_phase(&app), GamePhase::GameOver);
+    let high_scores = app.world().resource::<HighScores>();
+    let entry = &high_scores.get_top_scores()[0];
+    assert_eq!(entry.name, "BBZ");
+    assert_eq!(entry.score, 50);
+    assert_eq!(entry.wave, 1);
+    assert!(entry.is_current);
+    assert!(entry.date.is_some());
+}
+
+#[test]
+fn initials_wrap_around_the_alphabet() {
+    let mut initials = InitialsEntry::default();
+    initials.cycle(-1);
+    assert_eq!(initials.name(), "ZAA");
+
+    initials.move_cursor(5);
+    initials.cycle(27);
+    assert_eq!(initials.name(), "ZAB");
+}
+
+#[test]
+fn only_scores_beating_the_table_qualify() {
+    let mut high_scores = HighScores::default();
+    assert!(!high_scores.qualifies(0));
+    for score in [50, 40, 30, 20, 10] {
+        high_scores.add_score(score);
+    }
+
+    assert!(!high_scores.qualifies(10));
+    assert!(high_scores.qualifies(11));
+}
+
+#[test]
+fn table_size_is_limited_to_what_fits_on_screen() {
+    let mut config = GameConfig::default();
+    config.high_scores.table_size = HIGH_SCORE_TABLE_MAX_SIZE;
+    assert!(config.validate().is_ok());
+
+    config.high_scores.table_size = HIGH_SCORE_TABLE_MAX_SIZE + 1;
+    assert!(matches!(
+        config.validate(),
+        Err(GameConfigError::Invalid {
+            field: "high_scores.table_size",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn largest_table_rows_end_above_the_back_menu() {
+    let mut app = game_app();
+    {
+        let mut high_scores = app.world_mut().resource_mut::<HighScores>();
+        high_scores.set_capacity(HIGH_SCORE_TABLE_MAX_SIZE);
+        for score in 1..=HIGH_SCORE_TABLE_MAX_SIZE as u32 {
+            high_scores.add_score(score * 10);
+        }
+    }
+    enter(&mut app, GamePhase::HighScores);
+
+    let rows: Vec<(f32, f32)> = app
+        .world_mut()
+        .query::<(&Text, &TextFont, &Node)>()
+        .iter(app.world())
+        .filter(|(text, _, _)| text.0.contains("Wave"))
+        .map(|(_, font, node)| match node.top {
+            Val::Px(top) => (top, font.font_size),
+            _ => panic!("rows are placed in pixels"),
+        })
+        .collect();
+    assert_eq!(rows.len(), HIGH_SCORE_TABLE_MAX_SIZE);
+    // The Back menu sits 50 px above the bottom edge
+    let lowest = rows
+        .iter()
+        .map(|(top, font_size)| top + font_size / 0.75)
+        .fold(0.0, f32::max);
+    assert!(lowest <= WINDOW_HEIGHT - 90.0, "last row ends at {lowest}");
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::input::*;
+use vibe_invaders::settings::*;
+use vibe_invaders::storage::*;
+
+#[test]
+fn settings_survive_a_restart() {
+    let storage = temp_storage("round-trip");
+    let mut settings = Settings {
+        music_volume: 0.25,
+        display_mode: DisplayMode::Exclusive,
+        starfield_density: 0.5,
+        language: "de".to_string(),
+        ..default()
+    };
+    settings.bindings.fire = vec![Binding::Key(KeyCode::KeyZ), Binding::Key(KeyCode::Enter)];
+    storage.save(&settings);
+
+    let loaded: Settings = storage.load();
+
+    assert!(loaded == settings);
+    let text = storage.read_raw(Settings::KEY).unwrap().unwrap();
+    assert!(text.contains("\"KeyZ\""));
+}
+
+#[test]
+fn unknown_key_name_falls_back_to_default_settings() {
+    let storage = temp_storage("unknown-key");
+    storage
+        .write_raw(
+            Settings::KEY,
+            "(version: 1, data: (key_bindings: (fire: [\"NoSuchKey\"])))",
+        )
+        .unwrap();
+
+    let loaded: Settings = storage.load();
+
+    assert!(loaded == Settings::default());
+}
+
+#[test]
+fn older_fullscreen_flag_loads_as_a_display_mode() {
+    let storage = temp_storage("fullscreen-flag");
+    storage
+        .write_raw(Settings::KEY, "(version: 1, data: (fullscreen: true))")
+        .unwrap();
+
+    let loaded: Settings = storage.load();
+
+    assert_eq!(loaded.display_mode, DisplayMode::Borderless);
+}
+
+#[test]
+fn f11_and_alt_enter_toggle_fullscreen() {
+    let mut app = game_app();
+    tap(&mut app, KeyCode::F11);
+    assert_eq!(
+        app.world().resource::<Settings>().display_mode,
+        DisplayMode::Borderless
+    );
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::AltLeft);
+    tap(&mut app, KeyCode::Enter);
+    assert_eq!(
+        app.world().resource::<Settings>().display_mode,
+        DisplayMode::Windowed
+    );
+
+    // The settings screen cycles through every mode
+    let mut mode = DisplayMode::Windowed;
+    for _ in 0..DisplayMode::AVAILABLE.len() {
+        mode = mode.cycle(1);
+    }
+    assert_eq!(mode, DisplayMode::Windowed);
+    assert_eq!(DisplayMode::Exclusive.cycle(1), DisplayMode::Windowed);
+}
+
+#[test]
+fn rebound_fire_key_shoots() {
+    let mut app = game_app();
+    app.world_mut().resource_mut::<Settings>().bindings.fire = vec![Binding::Key(KeyCode::KeyZ)];
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(count::<Bullet>(&mut app), 0);
+
+    tap(&mut app, KeyCode::KeyZ);
+    assert_eq!(count::<Bullet>(&mut app), 1);
+}
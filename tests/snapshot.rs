@@ -0,0 +1,168 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::components::*;
+use vibe_invaders::config::GameConfig;
+use vibe_invaders::resources::*;
+use vibe_invaders::snapshot::*;
+use vibe_invaders::storage::*;
+
+fn enemy_positions(app: &mut App) -> Vec<Vec2> {
+    let mut positions: Vec<Vec2> = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+    positions
+}
+
+#[test]
+fn suspended_run_continues_where_it_stopped() {
+    let mut app = game_app();
+    app.insert_resource(temp_storage("continue"));
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(120.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(-100.0, 150.0));
+    spawn_enemy(&mut app, Vec2::new(100.0, 150.0));
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, 100.0), Vec2::new(0.0, -50.0));
+    app.world_mut().resource_mut::<GameState>().score = 70;
+    app.world_mut().insert_resource(GameRng::seeded(7));
+    advance(&mut app, 1);
+    let enemies_before = enemy_positions(&mut app);
+
+    suspend(&mut app);
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+    assert_eq!(count::<Enemy>(&mut app), 0);
+    assert_eq!(count::<EnemyBullet>(&mut app), 0);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_some());
+
+    choose(&mut app, MenuAction::Continue);
+
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    assert_eq!(enemy_positions(&mut app), enemies_before);
+    assert_eq!(count::<EnemyBullet>(&mut app), 1);
+    assert_eq!(app.world().resource::<GameState>().score, 70);
+    let transform = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .unwrap();
+    assert_eq!(transform.translation.x, 120.0);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_none());
+    let saved = app.world().resource::<Storage>().read::<SuspendedRun>();
+    assert!(saved.unwrap().is_none(), "a run can only be continued once");
+}
+
+#[test]
+fn suspended_run_is_saved_and_reloaded() {
+    let storage = temp_storage("reload");
+    let mut app = game_app();
+    app.insert_resource(storage.clone());
+    enter_empty_arena(&mut app);
+    spawn_enemy(&mut app, Vec2::new(0.0, 150.0));
+    app.world_mut().resource_mut::<GameState>().score = 30;
+    suspend(&mut app);
+
+    let loaded: SuspendedRun = storage.load();
+
+    let snapshot = loaded.snapshot.expect("the run was saved");
+    assert_eq!(snapshot.enemies.len(), 1);
+    assert_eq!(snapshot.game_state.score, 30);
+}
+
+#[test]
+fn snapshot_from_before_autofire_still_loads() {
+    let storage = temp_storage("before-autofire");
+    let mut app = game_app();
+    app.insert_resource(storage.clone());
+    enter_empty_arena(&mut app);
+    app.world_mut().resource_mut::<GameState>().score = 40;
+    suspend(&mut app);
+
+    // Saves written before autofire have no fire timer
+    let text = storage.read_raw(SuspendedRun::KEY).unwrap().unwrap();
+    let older: String = text
+        .lines()
+        .filter(|line| !line.contains("player_fire_timer"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_ne!(older, text);
+    storage.write_raw(SuspendedRun::KEY, &older).unwrap();
+
+    let loaded = storage.read::<SuspendedRun>().unwrap().unwrap();
+
+    let snapshot = loaded.snapshot.expect("the run was saved");
+    assert_eq!(snapshot.game_state.score, 40);
+    assert_eq!(snapshot.game_state.player_fire_timer, 0.0);
+}
+
+#[test]
+fn new_game_discards_the_suspended_run() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    suspend(&mut app);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_some());
+
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 1);
+
+    assert_eq!(current_phase(&app), GamePhase::Playing);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_none());
+}
+
+#[test]
+fn snapshot_from_another_version_is_rejected() {
+    let storage = temp_storage("version");
+    storage
+        .write_raw(SuspendedRun::KEY, "(version: 0, data: (snapshot: None))")
+        .unwrap();
+
+    assert!(matches!(
+        storage.read::<SuspendedRun>(),
+        Err(StorageError::Version { found: 0, .. })
+    ));
+    assert!(storage.load::<SuspendedRun>().snapshot.is_none());
+}
+
+#[test]
+fn snapshot_from_another_build_is_discarded() {
+    let storage = temp_storage("build");
+    storage
+        .write_raw(
+            SuspendedRun::KEY,
+            "(version: 3, build: Some(\"0.1.0\"), data: (snapshot: None))",
+        )
+        .unwrap();
+
+    assert!(matches!(
+        storage.read::<SuspendedRun>(),
+        Err(StorageError::Build { .. })
+    ));
+    assert!(storage.load::<SuspendedRun>().snapshot.is_none());
+    // An update is not corruption, so nothing is kept aside
+    let backup = storage.read_raw(&format!("{}.corrupt", SuspendedRun::KEY));
+    assert_eq!(backup.unwrap(), None);
+}
+
+#[test]
+fn changed_content_discards_the_suspended_run() {
+    let storage = temp_storage("content");
+    let mut app = game_app();
+    app.insert_resource(storage.clone());
+    enter_empty_arena(&mut app);
+    spawn_enemy(&mut app, Vec2::new(0.0, 150.0));
+    suspend(&mut app);
+    advance(&mut app, 1);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_some());
+
+    // The config file was edited since the run was suspended
+    app.world_mut().resource_mut::<GameConfig>().fingerprint ^= 1;
+    advance(&mut app, 2);
+
+    assert_eq!(current_phase(&app), GamePhase::StartScreen);
+    assert!(app.world().resource::<SuspendedRun>().snapshot.is_none());
+    assert_eq!(storage.read_raw(SuspendedRun::KEY).unwrap(), None);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use vibe_invaders::messages::RunOutcome;
+use vibe_invaders::resources::*;
+
+#[test]
+fn run_stats_count_shots_hits_and_kills() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    spawn_enemy(&mut app, Vec2::new(0.0, 100.0));
+
+    // One shot hits, the second flies past the destroyed enemy
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+    tap(&mut app, KeyCode::Space);
+    advance(&mut app, 60);
+
+    let stats = &app.world().resource::<RunStats>().stats;
+    assert_eq!(stats.shots_fired, 2);
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.kills.type1, 1);
+    assert_eq!(stats.accuracy(), 0.5);
+}
+
+#[test]
+fn finished_run_is_added_to_lifetime_stats() {
+    let mut app = game_app();
+    enter_empty_arena(&mut app);
+    spawn_player(&mut app, Vec2::new(0.0, -250.0));
+    last_life(&mut app);
+    fire_enemy_bullet(&mut app, Vec2::new(0.0, -200.0), Vec2::new(0.0, -400.0));
+    advance(&mut app, 30);
+
+    let run_stats = app.world().resource::<RunStats>();
+    assert_eq!(run_stats.outcome, Some(RunOutcome::Shot));
+    let lifetime = app.world().resource::<LifetimeStats>();
+    assert_eq!(lifetime.runs, 1);
+    assert_eq!(lifetime.totals.outcomes.shot, 1);
+    assert!(lifetime.totals.time_survived > 0.0);
+}
+
+#[test]
+fn lifetime_stats_accumulate_runs() {
+    let mut lifetime = LifetimeStats::default();
+    let mut run = Stats {
+        shots_fired: 10,
+        hits: 4,
+        ..default()
+    };
+    run.outcomes.invaded = 1;
+
+    lifetime.record(&run, 120);
+    lifetime.record(&run, 80);
+
+    assert_eq!(lifetime.runs, 2);
+    assert_eq!(lifetime.best_score, 120);
+    assert_eq!(lifetime.totals.shots_fired, 20);
+    assert_eq!(lifetime.totals.outcomes.invaded, 2);
+    assert_eq!(lifetime.totals.accuracy(), 0.4);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+use vibe_invaders::resources::*;
+use vibe_invaders::storage::*;
+
+#[test]
+fn high_scores_survive_a_restart() {
+    let storage = temp_storage("round-trip");
+    let mut high_scores = HighScores::default();
+    high_scores.add_score(120);
+    high_scores.add_score(80);
+    storage.save(&high_scores);
+
+    let loaded: HighScores = storage.load();
+
+    let scores: Vec<u32> = loaded.scores.iter().map(|entry| entry.score).collect();
+    assert_eq!(scores, [120, 80]);
+    assert!(loaded.scores.iter().all(|entry| !entry.is_current));
+}
+
+#[test]
+fn corrupt_save_is_replaced_by_default_and_kept_aside() {
+    let storage = temp_storage("corrupt");
+    storage
+        .write_raw(HighScores::KEY, "(version: 1, data: [[[")
+        .unwrap();
+
+    let loaded: HighScores = storage.load();
+
+    assert!(loaded.scores.is_empty());
+    let backup = storage.read_raw(&format!("{}.corrupt", HighScores::KEY));
+    assert_eq!(backup.unwrap().as_deref(), Some("(version: 1, data: [[["));
+}
+
+#[test]
+fn save_from_another_version_is_rejected() {
+    let storage = temp_storage("version");
+    storage
+        .write_raw(HighScores::KEY, "(version: 99, data: (scores: []))")
+        .unwrap();
+
+    let result = storage.read::<HighScores>();
+
+    assert!(matches!(
+        result,
+        Err(StorageError::Version {
+            found: 99,
+            expected: 1
+        })
+    ));
+}
+
+#[test]
+fn table_size_is_configurable() {
+    let mut high_scores = HighScores::default();
+    high_scores.set_capacity(3);
+    for score in [10, 40, 20, 30] {
+        high_scores.add_score(score);
+    }
+
+    let scores: Vec<u32> = high_scores.scores.iter().map(|entry| entry.score).collect();
+    assert_eq!(scores, [40, 30, 20]);
+}
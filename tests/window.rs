@@ -0,0 +1,77 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResolution};
+use common::*;
+use vibe_invaders::resources::*;
+
+fn viewport(app: &mut App) -> (UVec2, UVec2) {
+    let mut cameras = app.world_mut().query::<&Camera>();
+    let viewport = cameras
+        .single(app.world())
+        .unwrap()
+        .viewport
+        .clone()
+        .unwrap();
+    (viewport.physical_position, viewport.physical_size)
+}
+
+#[test]
+fn window_positions_map_to_the_centered_playfield() {
+    let window_dims = WindowDimensions {
+        width: 1600.0,
+        height: 900.0,
+    };
+    assert_eq!(window_dims.playfield_scale(), 1.5);
+    assert_eq!(
+        window_dims.to_playfield(Vec2::new(800.0, 450.0)),
+        Vec2::ZERO
+    );
+    assert_eq!(
+        window_dims.to_playfield(Vec2::new(200.0, 0.0)),
+        Vec2::new(-400.0, 300.0)
+    );
+}
+
+#[test]
+fn resizing_the_window_letterboxes_the_playfield() {
+    let mut app = game_app();
+    let window = app
+        .world_mut()
+        .spawn((
+            Window {
+                resolution: WindowResolution::new(1600, 900),
+                ..default()
+            },
+            PrimaryWindow,
+        ))
+        .id();
+    app.world_mut().spawn(Camera2d);
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<WindowDimensions>(),
+        WindowDimensions {
+            width: 1600.0,
+            height: 900.0
+        }
+    );
+    assert_eq!(
+        viewport(&mut app),
+        (UVec2::new(200, 0), UVec2::new(1200, 900))
+    );
+    assert_eq!(app.world().resource::<UiScale>().0, 1.5);
+
+    // A tall window gets bars above and below instead
+    app.world_mut()
+        .get_mut::<Window>(window)
+        .unwrap()
+        .resolution
+        .set(400.0, 600.0);
+    app.update();
+    assert_eq!(
+        viewport(&mut app),
+        (UVec2::new(0, 150), UVec2::new(400, 300))
+    );
+    assert_eq!(app.world().resource::<UiScale>().0, 0.5);
+}